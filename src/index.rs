@@ -1,24 +1,197 @@
 use crate::utils::{cosine_similarity, euclidean_distance};
-use crate::Result;
+use crate::{Result, VectorDBError};
+use anyhow::{bail, Context};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use ndarray::Array1;
 use rand::Rng;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
 use uuid::Uuid;
 
 pub trait Index {
     fn build(&mut self, vectors: &[(&Uuid, &Array1<f32>)]) -> Result<()>;
     fn query(&self, query: &Array1<f32>, top_k: usize) -> Result<Vec<(Uuid, f32)>>;
+    /// Adds a single vector to an already-built index, without requiring a
+    /// full rebuild. Errors with [`VectorDBError::DuplicateId`] if `id` is
+    /// already present.
+    fn insert(&mut self, id: &Uuid, vector: &Array1<f32>) -> Result<()>;
+    /// Removes a single vector from the index. Errors with
+    /// [`VectorDBError::MissingId`] if `id` isn't present.
+    fn remove(&mut self, id: &Uuid) -> Result<()>;
+    /// Like [`Self::query`], but only ids for which `predicate` returns
+    /// `true` are eligible to appear in the result. Implementations should
+    /// search until `top_k` passing results are found (or the index is
+    /// exhausted) rather than taking the top `top_k` unfiltered candidates
+    /// and then filtering, which would silently under-return for selective
+    /// predicates.
+    fn query_filtered(
+        &self,
+        query: &Array1<f32>,
+        top_k: usize,
+        predicate: &dyn Fn(&Uuid) -> bool,
+    ) -> Result<Vec<(Uuid, f32)>>;
+    /// Like [`Self::query`], but lets the caller pick the similarity
+    /// convention per call via `style`, independent of whatever metric the
+    /// index was built with, and relax the fixed `top_k`: `limit = None`
+    /// returns every match that passes `threshold` instead of a capped
+    /// count, and `threshold = None` skips filtering entirely. Lets callers
+    /// do "find everything within 0.8 cosine" style queries instead of
+    /// always requesting a fixed count and filtering afterwards.
+    fn query_with_options(
+        &self,
+        query: &Array1<f32>,
+        limit: Option<usize>,
+        threshold: Option<f32>,
+        style: SimilarityStyle,
+    ) -> Result<Vec<(Uuid, f32)>>;
     fn clear(&mut self);
 }
 
+/// Similarity convention for [`Index::query_with_options`]: controls both
+/// how a score compares against an optional threshold and how results are
+/// ordered. Distinct from [`DistanceMetric`], which governs how a whole
+/// index is built and scored; `SimilarityStyle` only affects one ad hoc
+/// query, regardless of the index's own metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimilarityStyle {
+    /// Higher is better; a threshold is a minimum.
+    Cosine,
+    /// Lower is better; a threshold is a maximum.
+    EuclideanDistance,
+    /// Higher is better; a threshold is a minimum.
+    DotProduct,
+}
+
+impl SimilarityStyle {
+    fn score(&self, a: &Array1<f32>, b: &Array1<f32>) -> f32 {
+        match self {
+            SimilarityStyle::Cosine => cosine_similarity(a, b),
+            SimilarityStyle::EuclideanDistance => euclidean_distance(a, b),
+            SimilarityStyle::DotProduct => a.dot(b),
+        }
+    }
+
+    /// Whether `score` passes `threshold` under this style's ordering
+    /// convention: higher-is-better styles require `score >= threshold`,
+    /// while `EuclideanDistance` (lower is better) requires `score <= threshold`.
+    fn passes(&self, score: f32, threshold: f32) -> bool {
+        match self {
+            SimilarityStyle::EuclideanDistance => score <= threshold,
+            SimilarityStyle::Cosine | SimilarityStyle::DotProduct => score >= threshold,
+        }
+    }
+
+    /// Orders `a` before `b` when `a` is the better match under this style.
+    fn cmp(&self, a: f32, b: f32) -> std::cmp::Ordering {
+        match self {
+            SimilarityStyle::EuclideanDistance => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+            SimilarityStyle::Cosine | SimilarityStyle::DotProduct => {
+                b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal)
+            }
+        }
+    }
+}
+
+/// Distance/similarity metric shared across the `Index` implementations,
+/// mirroring the metric knob on systems like cozo's HNSW index. Every
+/// variant exposes a single [`Self::score`] where higher always means
+/// "closer", so ranking code never needs to special-case the metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// Cosine similarity; higher is closer, range `[-1, 1]`.
+    Cosine,
+    /// Negative Euclidean (L2) distance; higher is closer.
+    L2,
+    /// Raw inner product; higher is closer. Not symmetric as a "distance" in
+    /// the usual sense, so graph-building code that needs a symmetric notion
+    /// of closeness (e.g. HNSW edge pruning) falls back to cosine instead —
+    /// see [`Self::graph_distance`].
+    InnerProduct,
+}
+
+impl DistanceMetric {
+    /// Final ranking score for this metric; always "higher is closer".
+    fn score(&self, a: &Array1<f32>, b: &Array1<f32>) -> f32 {
+        match self {
+            DistanceMetric::Cosine => cosine_similarity(a, b),
+            DistanceMetric::L2 => -euclidean_distance(a, b),
+            DistanceMetric::InnerProduct => a.dot(b),
+        }
+    }
+
+    /// A symmetric notion of distance ("lower is closer") suitable for graph
+    /// construction: greedy descent, the diversity heuristic, and LSH
+    /// bucketing all need `dist(a, b) == dist(b, a)`, which raw inner
+    /// product cannot guarantee. Cosine and L2 are already symmetric;
+    /// inner product uses normalized cosine distance as its proxy.
+    fn graph_distance(&self, a: &Array1<f32>, b: &Array1<f32>) -> f32 {
+        match self {
+            DistanceMetric::Cosine => 1.0 - cosine_similarity(a, b),
+            DistanceMetric::L2 => euclidean_distance(a, b),
+            DistanceMetric::InnerProduct => 1.0 - cosine_similarity(a, b),
+        }
+    }
+
+    /// Stable byte tag used by the on-disk index formats; the discriminant
+    /// values are part of the file format and must not be reordered.
+    fn to_byte(self) -> u8 {
+        match self {
+            DistanceMetric::Cosine => 0,
+            DistanceMetric::L2 => 1,
+            DistanceMetric::InnerProduct => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> anyhow::Result<Self> {
+        match byte {
+            0 => Ok(DistanceMetric::Cosine),
+            1 => Ok(DistanceMetric::L2),
+            2 => Ok(DistanceMetric::InnerProduct),
+            other => bail!("unknown distance metric tag {other} in index file"),
+        }
+    }
+}
+
+/// Shared tail end of [`Index::query_with_options`] for every implementor:
+/// drop anything that fails `threshold` (if given), order by `style`, then
+/// cap at `limit` (if given).
+fn rank_with_options(
+    mut results: Vec<(Uuid, f32)>,
+    limit: Option<usize>,
+    threshold: Option<f32>,
+    style: SimilarityStyle,
+) -> Vec<(Uuid, f32)> {
+    if let Some(threshold) = threshold {
+        results.retain(|(_, score)| style.passes(*score, threshold));
+    }
+    results.sort_by(|a, b| style.cmp(a.1, b.1));
+    if let Some(limit) = limit {
+        results.truncate(limit);
+    }
+    results
+}
+
 pub struct BruteForceIndex {
     indexed_vectors: Vec<(Uuid, Array1<f32>)>,
+    metric: DistanceMetric,
 }
 
 impl BruteForceIndex {
     pub fn new() -> Self {
         Self {
             indexed_vectors: Vec::new(),
+            metric: DistanceMetric::Cosine,
+        }
+    }
+
+    /// Create a new brute-force index scored by `metric` instead of cosine.
+    pub fn with_metric(metric: DistanceMetric) -> Self {
+        Self {
+            indexed_vectors: Vec::new(),
+            metric,
         }
     }
 
@@ -59,7 +232,63 @@ impl Index for BruteForceIndex {
     }
 
     fn query(&self, query: &Array1<f32>, top_k: usize) -> Result<Vec<(Uuid, f32)>> {
-        Ok(self.query_with_similarity(query, top_k, true)) // Default to cosine similarity
+        let mut results: Vec<(Uuid, f32)> = self
+            .indexed_vectors
+            .iter()
+            .map(|(id, vector)| (*id, self.metric.score(query, vector)))
+            .collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(top_k);
+        Ok(results)
+    }
+
+    fn insert(&mut self, id: &Uuid, vector: &Array1<f32>) -> Result<()> {
+        if self.indexed_vectors.iter().any(|(existing, _)| existing == id) {
+            return Err(VectorDBError::DuplicateId(*id));
+        }
+        self.indexed_vectors.push((*id, vector.clone()));
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &Uuid) -> Result<()> {
+        let original_len = self.indexed_vectors.len();
+        self.indexed_vectors.retain(|(existing, _)| existing != id);
+        if self.indexed_vectors.len() == original_len {
+            return Err(VectorDBError::MissingId(*id));
+        }
+        Ok(())
+    }
+
+    fn query_filtered(
+        &self,
+        query: &Array1<f32>,
+        top_k: usize,
+        predicate: &dyn Fn(&Uuid) -> bool,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let mut results: Vec<(Uuid, f32)> = self
+            .indexed_vectors
+            .iter()
+            .filter(|(id, _)| predicate(id))
+            .map(|(id, vector)| (*id, self.metric.score(query, vector)))
+            .collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(top_k);
+        Ok(results)
+    }
+
+    fn query_with_options(
+        &self,
+        query: &Array1<f32>,
+        limit: Option<usize>,
+        threshold: Option<f32>,
+        style: SimilarityStyle,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let results: Vec<(Uuid, f32)> = self
+            .indexed_vectors
+            .iter()
+            .map(|(id, vector)| (*id, style.score(query, vector)))
+            .collect();
+        Ok(rank_with_options(results, limit, threshold, style))
     }
 
     fn clear(&mut self) {
@@ -73,6 +302,10 @@ impl Default for BruteForceIndex {
     }
 }
 
+/// Magic number and format version for [`LSHIndex::save`]/[`LSHIndex::load`].
+const LSH_INDEX_MAGIC: &[u8; 4] = b"LSHI";
+const LSH_INDEX_VERSION: u32 = 1;
+
 /// Locality-Sensitive Hashing (LSH) index using random hyperplane projection.
 /// This index approximates nearest neighbour search by hashing vectors into
 /// buckets based on the sign of their projection onto a set of random
@@ -80,6 +313,8 @@ impl Default for BruteForceIndex {
 /// the query are compared, providing a faster albeit approximate search.
 pub struct LSHIndex {
     num_planes: usize,
+    max_probes: usize,
+    metric: DistanceMetric,
     hyperplanes: Vec<Array1<f32>>,
     buckets: HashMap<u64, Vec<(Uuid, Array1<f32>)>>,
     all_vectors: Vec<(Uuid, Array1<f32>)>,
@@ -87,9 +322,25 @@ pub struct LSHIndex {
 
 impl LSHIndex {
     /// Create a new LSH index with the specified number of hyperplanes.
-    pub fn new(num_planes: usize) -> Self {
+    /// `max_probes` bounds how many extra buckets a query will inspect via
+    /// multi-probe LSH (see [`Self::query_bucket`]) beyond its own bucket.
+    /// Buckets by the sign of the (cosine-equivalent) hyperplane projection
+    /// regardless of metric, then reranks candidates within and across
+    /// buckets by [`DistanceMetric::score`].
+    pub fn new(num_planes: usize, max_probes: usize) -> Self {
+        Self::with_metric(num_planes, max_probes, DistanceMetric::Cosine)
+    }
+
+    /// Same as [`Self::new`] but candidates are reranked by `metric` instead
+    /// of cosine similarity. Hyperplane hashing stays angle-based (hashing
+    /// normalization is metric-independent, since scaling a vector never
+    /// flips the sign of its projection onto a plane), so only the final
+    /// scoring pass changes.
+    pub fn with_metric(num_planes: usize, max_probes: usize, metric: DistanceMetric) -> Self {
         Self {
             num_planes,
+            max_probes,
+            metric,
             hyperplanes: Vec::new(),
             buckets: HashMap::new(),
             all_vectors: Vec::new(),
@@ -106,18 +357,224 @@ impl LSHIndex {
         hash
     }
 
+    /// Ranks hyperplane indices by ascending `|dot(query, plane)|`: the
+    /// planes the query sits closest to, and therefore the ones most likely
+    /// to have put a true neighbour on the wrong side of the query's hash.
+    fn rank_planes_by_closeness(&self, query: &Array1<f32>) -> Vec<usize> {
+        let mut ranked: Vec<(usize, f32)> = self
+            .hyperplanes
+            .iter()
+            .enumerate()
+            .map(|(i, plane)| (i, query.dot(plane).abs()))
+            .collect();
+        ranked.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.into_iter().map(|(i, _)| i).collect()
+    }
+
+    fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+        fn helper(start: usize, n: usize, k: usize, current: &mut Vec<usize>, result: &mut Vec<Vec<usize>>) {
+            if current.len() == k {
+                result.push(current.clone());
+                return;
+            }
+            for i in start..n {
+                current.push(i);
+                helper(i + 1, n, k, current, result);
+                current.pop();
+            }
+        }
+        let mut result = Vec::new();
+        helper(0, n, k, &mut Vec::new(), &mut result);
+        result
+    }
+
+    /// Generates perturbation bit-masks in increasing total-perturbation
+    /// order (fewest flipped bits first, ties broken by summed rank), capped
+    /// at `self.max_probes` masks. XORing the query's hash with each mask in
+    /// order produces the sequence of neighbouring buckets multi-probe LSH
+    /// should inspect, preferring to flip the bits the query was closest to
+    /// since those are most likely to have misclassified a true neighbour.
+    fn perturbation_masks(&self, ranked_planes: &[usize]) -> Vec<u64> {
+        let n = ranked_planes.len();
+        let mut masks = Vec::new();
+        'sizes: for size in 1..=n {
+            let mut combos = Self::combinations(n, size);
+            combos.sort_by_key(|c| c.iter().sum::<usize>());
+            for combo in combos {
+                let mask = combo
+                    .iter()
+                    .fold(0u64, |acc, &pos| acc | (1u64 << ranked_planes[pos]));
+                masks.push(mask);
+                if masks.len() >= self.max_probes {
+                    break 'sizes;
+                }
+            }
+        }
+        masks
+    }
+
     fn query_bucket(&self, query: &Array1<f32>, top_k: usize) -> Vec<(Uuid, f32)> {
-        let hash = self.compute_hash(query);
-        let candidates = self.buckets.get(&hash).cloned().unwrap_or_default();
+        let base_hash = self.compute_hash(query);
+        let ranked_planes = self.rank_planes_by_closeness(query);
+        let masks = self.perturbation_masks(&ranked_planes);
+
+        let mut probed: HashSet<u64> = HashSet::new();
+        let mut candidates: Vec<(Uuid, Array1<f32>)> = Vec::new();
+
+        probed.insert(base_hash);
+        if let Some(bucket) = self.buckets.get(&base_hash) {
+            candidates.extend(bucket.iter().cloned());
+        }
+
+        for mask in masks {
+            if candidates.len() >= top_k {
+                break;
+            }
+            let hash = base_hash ^ mask;
+            if probed.insert(hash) {
+                if let Some(bucket) = self.buckets.get(&hash) {
+                    candidates.extend(bucket.iter().cloned());
+                }
+            }
+        }
 
         let mut results: Vec<(Uuid, f32)> = candidates
             .iter()
-            .map(|(id, vector)| (*id, cosine_similarity(query, vector)))
+            .map(|(id, vector)| (*id, self.metric.score(query, vector)))
             .collect();
 
         results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         results.into_iter().take(top_k).collect()
     }
+
+    /// Serializes the hyperplane matrix and every indexed vector to a small
+    /// versioned binary format. The bucket map isn't stored: it's a pure
+    /// function of the hyperplanes and vectors, so [`Self::load`] rebuilds it
+    /// by re-hashing every loaded vector instead of duplicating it on disk.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.save_inner(path.as_ref()).map_err(VectorDBError::from)
+    }
+
+    fn save_inner(&self, path: &Path) -> anyhow::Result<()> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create LSH index file at {path:?}"))?;
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(LSH_INDEX_MAGIC)?;
+        writer.write_u32::<LittleEndian>(LSH_INDEX_VERSION)?;
+        let dim = self
+            .hyperplanes
+            .first()
+            .map(|p| p.len())
+            .or_else(|| self.all_vectors.first().map(|(_, v)| v.len()))
+            .unwrap_or(0);
+        writer.write_u32::<LittleEndian>(dim as u32)?;
+        writer.write_u32::<LittleEndian>(self.num_planes as u32)?;
+        writer.write_u32::<LittleEndian>(self.max_probes as u32)?;
+        writer.write_u8(self.metric.to_byte())?;
+
+        writer.write_u32::<LittleEndian>(self.hyperplanes.len() as u32)?;
+        for plane in &self.hyperplanes {
+            for &component in plane.iter() {
+                writer.write_f32::<LittleEndian>(component)?;
+            }
+        }
+
+        writer.write_u32::<LittleEndian>(self.all_vectors.len() as u32)?;
+        for (id, vector) in &self.all_vectors {
+            writer.write_all(id.as_bytes())?;
+            writer.write_u32::<LittleEndian>(vector.len() as u32)?;
+            for &component in vector.iter() {
+                writer.write_f32::<LittleEndian>(component)?;
+            }
+        }
+
+        writer.flush().context("failed to flush LSH index file")?;
+        Ok(())
+    }
+
+    /// Loads an index previously written by [`Self::save`], rebuilding the
+    /// bucket map by re-hashing every loaded vector against the loaded
+    /// hyperplanes. Rejects a file with the wrong magic number, an
+    /// unsupported format version, or a vector whose length doesn't match
+    /// the header's declared dimension.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::load_inner(path.as_ref()).map_err(VectorDBError::from)
+    }
+
+    fn load_inner(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open LSH index file at {path:?}"))?;
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0u8; 4];
+        reader
+            .read_exact(&mut magic)
+            .context("failed to read LSH index header")?;
+        if &magic != LSH_INDEX_MAGIC {
+            bail!("not an LSH index file (bad magic number)");
+        }
+
+        let version = reader.read_u32::<LittleEndian>()?;
+        if version != LSH_INDEX_VERSION {
+            bail!(
+                "unsupported LSH index format version {version} (expected {LSH_INDEX_VERSION})"
+            );
+        }
+
+        let dim = reader.read_u32::<LittleEndian>()? as usize;
+        let num_planes = reader.read_u32::<LittleEndian>()? as usize;
+        let max_probes = reader.read_u32::<LittleEndian>()? as usize;
+        let metric = DistanceMetric::from_byte(reader.read_u8()?)?;
+
+        let plane_count = reader.read_u32::<LittleEndian>()? as usize;
+        let mut hyperplanes = Vec::with_capacity(plane_count);
+        for _ in 0..plane_count {
+            let mut data = Vec::with_capacity(dim);
+            for _ in 0..dim {
+                data.push(reader.read_f32::<LittleEndian>()?);
+            }
+            hyperplanes.push(Array1::from(data));
+        }
+
+        let vector_count = reader.read_u32::<LittleEndian>()? as usize;
+        let mut all_vectors = Vec::with_capacity(vector_count);
+        for _ in 0..vector_count {
+            let mut id_bytes = [0u8; 16];
+            reader.read_exact(&mut id_bytes)?;
+            let id = Uuid::from_bytes(id_bytes);
+
+            let vector_dim = reader.read_u32::<LittleEndian>()? as usize;
+            if vector_dim != dim {
+                bail!("LSH index file declares dimension {dim} but vector {id} stores {vector_dim}");
+            }
+            let mut data = Vec::with_capacity(dim);
+            for _ in 0..dim {
+                data.push(reader.read_f32::<LittleEndian>()?);
+            }
+            all_vectors.push((id, Array1::from(data)));
+        }
+
+        let mut index = Self {
+            num_planes,
+            max_probes,
+            metric,
+            hyperplanes,
+            buckets: HashMap::new(),
+            all_vectors: Vec::new(),
+        };
+        for (id, vector) in all_vectors {
+            let hash = index.compute_hash(&vector);
+            index
+                .buckets
+                .entry(hash)
+                .or_insert_with(Vec::new)
+                .push((id, vector.clone()));
+            index.all_vectors.push((id, vector));
+        }
+
+        Ok(index)
+    }
 }
 
 impl Index for LSHIndex {
@@ -163,7 +620,7 @@ impl Index for LSHIndex {
             let mut all_results: Vec<(Uuid, f32)> = self
                 .all_vectors
                 .iter()
-                .map(|(id, vector)| (*id, cosine_similarity(query, vector)))
+                .map(|(id, vector)| (*id, self.metric.score(query, vector)))
                 .collect();
             all_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
             results = all_results.into_iter().take(top_k).collect();
@@ -172,6 +629,80 @@ impl Index for LSHIndex {
         Ok(results)
     }
 
+    fn insert(&mut self, id: &Uuid, vector: &Array1<f32>) -> Result<()> {
+        if self.all_vectors.iter().any(|(existing, _)| existing == id) {
+            return Err(VectorDBError::DuplicateId(*id));
+        }
+        let vec_clone = vector.clone();
+        let hash = self.compute_hash(&vec_clone);
+        self.buckets
+            .entry(hash)
+            .or_insert_with(Vec::new)
+            .push((*id, vec_clone.clone()));
+        self.all_vectors.push((*id, vec_clone));
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &Uuid) -> Result<()> {
+        let Some(pos) = self.all_vectors.iter().position(|(existing, _)| existing == id) else {
+            return Err(VectorDBError::MissingId(*id));
+        };
+        let (_, vector) = self.all_vectors.remove(pos);
+        let hash = self.compute_hash(&vector);
+        if let Some(bucket) = self.buckets.get_mut(&hash) {
+            bucket.retain(|(existing, _)| existing != id);
+        }
+        Ok(())
+    }
+
+    /// Filters within the probed buckets first (cheap), falling back to a
+    /// filtered brute-force scan over every vector if the selective
+    /// predicate leaves fewer than `top_k` passing candidates in the buckets
+    /// multi-probe happened to visit.
+    fn query_filtered(
+        &self,
+        query: &Array1<f32>,
+        top_k: usize,
+        predicate: &dyn Fn(&Uuid) -> bool,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let mut results: Vec<(Uuid, f32)> = self
+            .query_bucket(query, top_k)
+            .into_iter()
+            .filter(|(id, _)| predicate(id))
+            .collect();
+
+        if results.len() < top_k {
+            let mut all_results: Vec<(Uuid, f32)> = self
+                .all_vectors
+                .iter()
+                .filter(|(id, _)| predicate(id))
+                .map(|(id, vector)| (*id, self.metric.score(query, vector)))
+                .collect();
+            all_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            results = all_results.into_iter().take(top_k).collect();
+        }
+
+        Ok(results)
+    }
+
+    /// Scans every indexed vector rather than probing buckets, since a
+    /// threshold-bounded or unlimited query needs exact recall that
+    /// approximate bucket lookups can't guarantee.
+    fn query_with_options(
+        &self,
+        query: &Array1<f32>,
+        limit: Option<usize>,
+        threshold: Option<f32>,
+        style: SimilarityStyle,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let results: Vec<(Uuid, f32)> = self
+            .all_vectors
+            .iter()
+            .map(|(id, vector)| (*id, style.score(query, vector)))
+            .collect();
+        Ok(rank_with_options(results, limit, threshold, style))
+    }
+
     fn clear(&mut self) {
         self.buckets.clear();
         self.hyperplanes.clear();
@@ -181,16 +712,261 @@ impl Index for LSHIndex {
 
 impl Default for LSHIndex {
     fn default() -> Self {
-        Self::new(16)
+        Self::new(16, 16)
+    }
+}
+
+/// A node in one tree of an [`RPForestIndex`]. Internal nodes split their
+/// points with a random hyperplane; leaves hold the points directly once a
+/// subtree shrinks to `max_leaf_size` or fewer.
+enum RPNode {
+    Leaf(Vec<(Uuid, Array1<f32>)>),
+    Internal {
+        plane: Array1<f32>,
+        offset: f32,
+        above: Box<RPNode>,
+        below: Box<RPNode>,
+    },
+}
+
+/// Random-projection forest index (Annoy-style): an ensemble of binary trees,
+/// each splitting the dataset on random hyperplanes until leaves are small.
+/// A query descends every tree to its matching leaf, the candidates from all
+/// leaves are unioned, and the union is reranked by exact cosine similarity.
+/// More trees trade build time and memory for better recall, since a point
+/// near a split in one tree often lands on the same side as its true nearest
+/// neighbours in another.
+pub struct RPForestIndex {
+    n_trees: usize,
+    max_leaf_size: usize,
+    trees: Vec<RPNode>,
+    all_vectors: HashMap<Uuid, Array1<f32>>,
+}
+
+impl RPForestIndex {
+    /// Create a new RP-forest index with `n_trees` trees, each splitting
+    /// until a leaf holds at most `max_leaf_size` points.
+    pub fn new(n_trees: usize, max_leaf_size: usize) -> Self {
+        Self {
+            n_trees,
+            max_leaf_size: max_leaf_size.max(1),
+            trees: Vec::new(),
+            all_vectors: HashMap::new(),
+        }
+    }
+
+    fn build_tree(points: Vec<(Uuid, Array1<f32>)>, max_leaf_size: usize) -> RPNode {
+        if points.len() <= max_leaf_size {
+            return RPNode::Leaf(points);
+        }
+
+        let mut rng = rand::thread_rng();
+        let i = rng.gen_range(0..points.len());
+        let mut j = rng.gen_range(0..points.len());
+        while j == i && points.len() > 1 {
+            j = rng.gen_range(0..points.len());
+        }
+
+        if i == j {
+            // Every point is identical (or there's only one); no hyperplane
+            // can separate them further, so stop splitting.
+            return RPNode::Leaf(points);
+        }
+
+        let a = &points[i].1;
+        let b = &points[j].1;
+        let plane = a - b;
+        let midpoint = (a + b) * 0.5;
+        let offset = plane.dot(&midpoint);
+
+        let mut above = Vec::new();
+        let mut below = Vec::new();
+        for (id, vector) in points {
+            if plane.dot(&vector) >= offset {
+                above.push((id, vector));
+            } else {
+                below.push((id, vector));
+            }
+        }
+
+        // If the split failed to separate anything (e.g. duplicate points
+        // landing on the same side), stop recursing instead of looping
+        // forever on an unsplittable set.
+        if above.is_empty() || below.is_empty() {
+            let mut points = above;
+            points.extend(below);
+            return RPNode::Leaf(points);
+        }
+
+        RPNode::Internal {
+            plane,
+            offset,
+            above: Box::new(Self::build_tree(above, max_leaf_size)),
+            below: Box::new(Self::build_tree(below, max_leaf_size)),
+        }
+    }
+
+    fn rebuild_trees(&mut self) {
+        let points: Vec<(Uuid, Array1<f32>)> = self
+            .all_vectors
+            .iter()
+            .map(|(id, vector)| (*id, vector.clone()))
+            .collect();
+        self.trees = (0..self.n_trees)
+            .map(|_| Self::build_tree(points.clone(), self.max_leaf_size))
+            .collect();
+    }
+
+    fn collect_leaf_candidates(node: &RPNode, query: &Array1<f32>, candidates: &mut HashSet<Uuid>) {
+        match node {
+            RPNode::Leaf(points) => {
+                for (id, _) in points {
+                    candidates.insert(*id);
+                }
+            }
+            RPNode::Internal { plane, offset, above, below } => {
+                let side = plane.dot(query) >= *offset;
+                if side {
+                    Self::collect_leaf_candidates(above, query, candidates);
+                } else {
+                    Self::collect_leaf_candidates(below, query, candidates);
+                }
+            }
+        }
+    }
+}
+
+impl Index for RPForestIndex {
+    fn build(&mut self, vectors: &[(&Uuid, &Array1<f32>)]) -> Result<()> {
+        self.clear();
+
+        let points: Vec<(Uuid, Array1<f32>)> = vectors
+            .iter()
+            .map(|(id, vector)| (**id, (*vector).clone()))
+            .collect();
+        self.all_vectors = points.iter().cloned().collect();
+
+        self.trees = (0..self.n_trees)
+            .map(|_| Self::build_tree(points.clone(), self.max_leaf_size))
+            .collect();
+
+        Ok(())
+    }
+
+    fn query(&self, query: &Array1<f32>, top_k: usize) -> Result<Vec<(Uuid, f32)>> {
+        let mut candidates: HashSet<Uuid> = HashSet::new();
+        for tree in &self.trees {
+            Self::collect_leaf_candidates(tree, query, &mut candidates);
+        }
+
+        let mut results: Vec<(Uuid, f32)> = candidates
+            .into_iter()
+            .filter_map(|id| self.all_vectors.get(&id).map(|v| (id, cosine_similarity(query, v))))
+            .collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(top_k);
+        Ok(results)
+    }
+
+    /// Adds a point and rebuilds every tree from scratch. Unlike `HNSWIndex`,
+    /// the forest's trees have no incremental update rule — a random split
+    /// chosen for the old point set isn't valid for the new one — so a full
+    /// rebuild is the only way to keep every tree's invariants honest.
+    fn insert(&mut self, id: &Uuid, vector: &Array1<f32>) -> Result<()> {
+        if self.all_vectors.contains_key(id) {
+            return Err(VectorDBError::DuplicateId(*id));
+        }
+        self.all_vectors.insert(*id, vector.clone());
+        self.rebuild_trees();
+        Ok(())
+    }
+
+    /// Removes a point and rebuilds every tree from scratch; see
+    /// [`Self::insert`] for why a full rebuild is unavoidable here.
+    fn remove(&mut self, id: &Uuid) -> Result<()> {
+        if self.all_vectors.remove(id).is_none() {
+            return Err(VectorDBError::MissingId(*id));
+        }
+        self.rebuild_trees();
+        Ok(())
+    }
+
+    /// Filters the forest's candidate union first, falling back to a
+    /// filtered brute-force scan over every vector if that union doesn't
+    /// contain `top_k` passing candidates.
+    fn query_filtered(
+        &self,
+        query: &Array1<f32>,
+        top_k: usize,
+        predicate: &dyn Fn(&Uuid) -> bool,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let mut candidates: HashSet<Uuid> = HashSet::new();
+        for tree in &self.trees {
+            Self::collect_leaf_candidates(tree, query, &mut candidates);
+        }
+
+        let mut results: Vec<(Uuid, f32)> = candidates
+            .into_iter()
+            .filter(|id| predicate(id))
+            .filter_map(|id| self.all_vectors.get(&id).map(|v| (id, cosine_similarity(query, v))))
+            .collect();
+
+        if results.len() < top_k {
+            results = self
+                .all_vectors
+                .iter()
+                .filter(|(id, _)| predicate(id))
+                .map(|(id, v)| (*id, cosine_similarity(query, v)))
+                .collect();
+        }
+
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(top_k);
+        Ok(results)
+    }
+
+    /// Scans every indexed vector rather than descending the trees, for the
+    /// same exact-recall reason as [`LSHIndex::query_with_options`].
+    fn query_with_options(
+        &self,
+        query: &Array1<f32>,
+        limit: Option<usize>,
+        threshold: Option<f32>,
+        style: SimilarityStyle,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let results: Vec<(Uuid, f32)> = self
+            .all_vectors
+            .iter()
+            .map(|(id, vector)| (*id, style.score(query, vector)))
+            .collect();
+        Ok(rank_with_options(results, limit, threshold, style))
+    }
+
+    fn clear(&mut self) {
+        self.trees.clear();
+        self.all_vectors.clear();
+    }
+}
+
+impl Default for RPForestIndex {
+    fn default() -> Self {
+        Self::new(8, 16)
     }
 }
 
+/// Magic number and format version for [`HNSWIndex::save`]/[`HNSWIndex::load`].
+/// Bump `HNSW_INDEX_VERSION` whenever the binary layout changes so old files
+/// are rejected instead of misread.
+const HNSW_INDEX_MAGIC: &[u8; 4] = b"HNSI";
+const HNSW_INDEX_VERSION: u32 = 1;
+
 /// A simple implementation of the Hierarchical Navigable Small World (HNSW)
 /// graph for approximate nearest neighbour search. This implementation focuses
 /// on clarity over performance and is suitable for small datasets.
 pub struct HNSWIndex {
     m: usize,
     ef: usize,
+    metric: DistanceMetric,
     nodes: Vec<HNSWNode>,
     entry: Option<usize>,
     max_level: usize,
@@ -201,14 +977,60 @@ struct HNSWNode {
     vector: Array1<f32>,
     level: usize,
     neighbours: Vec<Vec<usize>>, // neighbours per level
+    /// Nodes are never physically removed from `nodes` (every neighbour list
+    /// references other nodes by index, so shrinking the vector would
+    /// invalidate them); `remove` instead tombstones the node here and
+    /// detaches its edges.
+    active: bool,
+}
+
+/// A node scored by its distance to whatever query is currently being
+/// resolved, ordered by that distance so it can sit in a [`BinaryHeap`]
+/// (ascending via `Reverse` for a candidate min-heap, descending as-is for a
+/// best-results max-heap).
+struct ScoredNode {
+    distance: f32,
+    idx: usize,
+}
+
+impl PartialEq for ScoredNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+impl Eq for ScoredNode {}
+
+impl PartialOrd for ScoredNode {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredNode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.distance
+            .partial_cmp(&other.distance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    }
 }
 
 impl HNSWIndex {
-    /// Create a new HNSW index.
+    /// Create a new HNSW index scored by cosine similarity.
     pub fn new(m: usize, ef: usize) -> Self {
+        Self::with_metric(m, ef, DistanceMetric::Cosine)
+    }
+
+    /// Create a new HNSW index scored by `metric`. Graph construction
+    /// (greedy descent, neighbour pruning) always uses
+    /// [`DistanceMetric::graph_distance`], a symmetric notion of closeness,
+    /// even for the non-symmetric `InnerProduct` metric; only the final
+    /// result scoring in [`Self::query`] uses the raw metric.
+    pub fn with_metric(m: usize, ef: usize, metric: DistanceMetric) -> Self {
         Self {
             m,
             ef,
+            metric,
             nodes: Vec::new(),
             entry: None,
             max_level: 0,
@@ -224,8 +1046,8 @@ impl HNSWIndex {
         level
     }
 
-    fn distance(a: &Array1<f32>, b: &Array1<f32>) -> f32 {
-        1.0 - cosine_similarity(a, b)
+    fn distance(&self, a: &Array1<f32>, b: &Array1<f32>) -> f32 {
+        self.metric.graph_distance(a, b)
     }
 
     fn insert_node(&mut self, id: Uuid, vector: Array1<f32>) {
@@ -236,6 +1058,7 @@ impl HNSWIndex {
             vector,
             level,
             neighbours: vec![Vec::new(); level + 1],
+            active: true,
         };
         if self.entry.is_none() {
             self.entry = Some(idx);
@@ -243,8 +1066,11 @@ impl HNSWIndex {
         }
         self.nodes.push(node);
 
-        // Connect to existing nodes
+        // Connect to existing (non-tombstoned) nodes
         for i in 0..idx {
+            if !self.nodes[i].active {
+                continue;
+            }
             let max_lvl = usize::min(level, self.nodes[i].level);
             for l in 0..=max_lvl {
                 self.nodes[idx].neighbours[l].push(i);
@@ -266,28 +1092,173 @@ impl HNSWIndex {
         }
     }
 
+    /// Prunes `node_idx`'s neighbour list at `level` down to at most `m`
+    /// entries using the HNSW diversity heuristic rather than a plain
+    /// "keep the M closest" cut. Candidates are considered in ascending
+    /// distance to the node; a candidate is accepted only if it's closer to
+    /// the node than it is to every neighbour already accepted, which keeps
+    /// edges spread across diverse directions instead of clustering around
+    /// whichever candidates happen to be nearest each other. If the strict
+    /// pass leaves fewer than `m` neighbours, the rejected candidates are
+    /// used to backfill in distance order.
     fn prune_neighbours(&mut self, node_idx: usize, level: usize) {
         let vector = self.nodes[node_idx].vector.clone();
-        let mut neigh = self.nodes[node_idx].neighbours[level]
+        let mut candidates = self.nodes[node_idx].neighbours[level]
             .clone()
             .into_iter()
             .map(|n| {
-                let d = Self::distance(&vector, &self.nodes[n].vector);
+                let d = self.distance(&vector, &self.nodes[n].vector);
                 (n, d)
             })
             .collect::<Vec<_>>();
-        neigh.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-        neigh.truncate(self.m);
-        self.nodes[node_idx].neighbours[level] = neigh.into_iter().map(|(n, _)| n).collect();
+        candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+        let mut selected: Vec<usize> = Vec::new();
+        let mut rejected: Vec<usize> = Vec::new();
+        for (candidate, dist_to_node) in &candidates {
+            if selected.len() >= self.m {
+                break;
+            }
+            let diverse = selected.iter().all(|&r| {
+                *dist_to_node < self.distance(&self.nodes[*candidate].vector, &self.nodes[r].vector)
+            });
+            if diverse {
+                selected.push(*candidate);
+            } else {
+                rejected.push(*candidate);
+            }
+        }
+
+        if selected.len() < self.m {
+            let remaining = self.m - selected.len();
+            selected.extend(rejected.into_iter().take(remaining));
+        }
+
+        self.nodes[node_idx].neighbours[level] = selected;
+    }
+
+    /// Runs the canonical HNSW `search_layer` at `level`: a best-first
+    /// expansion from `entry` bounded to at most `ef` results, using a
+    /// min-heap of unexplored candidates and a max-heap of the current best
+    /// `ef` results so the worst kept result can be evicted in O(log ef).
+    /// Returns up to `ef` `(node_idx, distance)` pairs sorted nearest-first.
+    fn search_layer(
+        &self,
+        query: &Array1<f32>,
+        entry: usize,
+        level: usize,
+        ef: usize,
+    ) -> Vec<(usize, f32)> {
+        let mut visited: HashSet<usize> = HashSet::new();
+        visited.insert(entry);
+
+        let entry_dist = self.distance(query, &self.nodes[entry].vector);
+        let mut candidates: BinaryHeap<Reverse<ScoredNode>> = BinaryHeap::new();
+        candidates.push(Reverse(ScoredNode { distance: entry_dist, idx: entry }));
+
+        let mut results: BinaryHeap<ScoredNode> = BinaryHeap::new();
+        results.push(ScoredNode { distance: entry_dist, idx: entry });
+
+        while let Some(Reverse(nearest)) = candidates.pop() {
+            let worst = results.peek().map(|r| r.distance).unwrap_or(f32::INFINITY);
+            if nearest.distance > worst && results.len() >= ef {
+                break;
+            }
+
+            for &n in &self.nodes[nearest.idx].neighbours[level] {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let dist = self.distance(query, &self.nodes[n].vector);
+                let worst = results.peek().map(|r| r.distance).unwrap_or(f32::INFINITY);
+                if results.len() < ef || dist < worst {
+                    candidates.push(Reverse(ScoredNode { distance: dist, idx: n }));
+                    results.push(ScoredNode { distance: dist, idx: n });
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        results
+            .into_sorted_vec()
+            .into_iter()
+            .map(|s| (s.idx, s.distance))
+            .collect()
+    }
+
+    /// Like [`Self::search_layer`], but `predicate` is consulted per node:
+    /// every visited node is still expanded for connectivity (so the search
+    /// can reach passing nodes on the far side of a rejected one), but only
+    /// passing nodes are eligible to enter `results`. Because a selective
+    /// predicate can make `ef` too small to reach `top_k` passing results,
+    /// the frontier keeps expanding until `results` holds `top_k` passing
+    /// candidates and no unexplored candidate could still improve on the
+    /// worst of them, or the reachable graph is exhausted.
+    fn search_layer_filtered(
+        &self,
+        query: &Array1<f32>,
+        entry: usize,
+        level: usize,
+        ef: usize,
+        top_k: usize,
+        predicate: &dyn Fn(&Uuid) -> bool,
+    ) -> Vec<(usize, f32)> {
+        let effective_ef = ef.max(top_k);
+        let mut visited: HashSet<usize> = HashSet::new();
+        visited.insert(entry);
+
+        let entry_dist = self.distance(query, &self.nodes[entry].vector);
+        let mut candidates: BinaryHeap<Reverse<ScoredNode>> = BinaryHeap::new();
+        candidates.push(Reverse(ScoredNode { distance: entry_dist, idx: entry }));
+
+        let mut results: BinaryHeap<ScoredNode> = BinaryHeap::new();
+        if predicate(&self.nodes[entry].id) {
+            results.push(ScoredNode { distance: entry_dist, idx: entry });
+        }
+
+        while let Some(Reverse(nearest)) = candidates.pop() {
+            if results.len() >= top_k {
+                let worst = results.peek().map(|r| r.distance).unwrap_or(f32::INFINITY);
+                if nearest.distance > worst {
+                    break;
+                }
+            }
+
+            for &n in &self.nodes[nearest.idx].neighbours[level] {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let dist = self.distance(query, &self.nodes[n].vector);
+                candidates.push(Reverse(ScoredNode { distance: dist, idx: n }));
+
+                if predicate(&self.nodes[n].id) {
+                    let worst = results.peek().map(|r| r.distance).unwrap_or(f32::INFINITY);
+                    if results.len() < effective_ef || dist < worst {
+                        results.push(ScoredNode { distance: dist, idx: n });
+                        if results.len() > effective_ef {
+                            results.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        results
+            .into_sorted_vec()
+            .into_iter()
+            .map(|s| (s.idx, s.distance))
+            .collect()
     }
 
     fn greedy_search(&self, query: &Array1<f32>, start: usize, level: usize) -> usize {
         let mut current = start;
         loop {
             let mut changed = false;
-            let mut best_dist = Self::distance(query, &self.nodes[current].vector);
+            let mut best_dist = self.distance(query, &self.nodes[current].vector);
             for &n in &self.nodes[current].neighbours[level] {
-                let dist = Self::distance(query, &self.nodes[n].vector);
+                let dist = self.distance(query, &self.nodes[n].vector);
                 if dist < best_dist {
                     best_dist = dist;
                     current = n;
@@ -300,6 +1271,142 @@ impl HNSWIndex {
         }
         current
     }
+
+    /// Serializes the full graph — node vectors, per-level adjacency,
+    /// `entry`, `max_level`, `m`, `ef`, and `metric` — to a small versioned
+    /// binary format, so a reloaded graph is query-ready without rebuilding
+    /// it through `build`. See [`Self::load`] for the format layout and the
+    /// checks applied on read.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.save_inner(path.as_ref()).map_err(VectorDBError::from)
+    }
+
+    fn save_inner(&self, path: &Path) -> anyhow::Result<()> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create HNSW index file at {path:?}"))?;
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(HNSW_INDEX_MAGIC)?;
+        writer.write_u32::<LittleEndian>(HNSW_INDEX_VERSION)?;
+        let dim = self.nodes.first().map(|n| n.vector.len()).unwrap_or(0);
+        writer.write_u32::<LittleEndian>(dim as u32)?;
+        writer.write_u32::<LittleEndian>(self.m as u32)?;
+        writer.write_u32::<LittleEndian>(self.ef as u32)?;
+        writer.write_u8(self.metric.to_byte())?;
+        writer.write_u32::<LittleEndian>(self.max_level as u32)?;
+        writer.write_i64::<LittleEndian>(self.entry.map(|e| e as i64).unwrap_or(-1))?;
+        writer.write_u32::<LittleEndian>(self.nodes.len() as u32)?;
+
+        for node in &self.nodes {
+            writer.write_all(node.id.as_bytes())?;
+            writer.write_u32::<LittleEndian>(node.level as u32)?;
+            writer.write_u8(if node.active { 1 } else { 0 })?;
+            writer.write_u32::<LittleEndian>(node.vector.len() as u32)?;
+            for &component in node.vector.iter() {
+                writer.write_f32::<LittleEndian>(component)?;
+            }
+            for level_neighbours in &node.neighbours {
+                writer.write_u32::<LittleEndian>(level_neighbours.len() as u32)?;
+                for &n in level_neighbours {
+                    writer.write_u32::<LittleEndian>(n as u32)?;
+                }
+            }
+        }
+
+        writer.flush().context("failed to flush HNSW index file")?;
+        Ok(())
+    }
+
+    /// Loads a graph previously written by [`Self::save`]. Rejects a file
+    /// with the wrong magic number or an unsupported format version, and
+    /// rejects one whose stored vectors don't all match the header's
+    /// declared dimension, rather than silently returning a corrupt or
+    /// stale graph.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::load_inner(path.as_ref()).map_err(VectorDBError::from)
+    }
+
+    fn load_inner(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open HNSW index file at {path:?}"))?;
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0u8; 4];
+        reader
+            .read_exact(&mut magic)
+            .context("failed to read HNSW index header")?;
+        if &magic != HNSW_INDEX_MAGIC {
+            bail!("not an HNSW index file (bad magic number)");
+        }
+
+        let version = reader.read_u32::<LittleEndian>()?;
+        if version != HNSW_INDEX_VERSION {
+            bail!(
+                "unsupported HNSW index format version {version} (expected {HNSW_INDEX_VERSION})"
+            );
+        }
+
+        let dim = reader.read_u32::<LittleEndian>()? as usize;
+        let m = reader.read_u32::<LittleEndian>()? as usize;
+        let ef = reader.read_u32::<LittleEndian>()? as usize;
+        let metric = DistanceMetric::from_byte(reader.read_u8()?)?;
+        let max_level = reader.read_u32::<LittleEndian>()? as usize;
+        let entry_raw = reader.read_i64::<LittleEndian>()?;
+        let entry = if entry_raw < 0 {
+            None
+        } else {
+            Some(entry_raw as usize)
+        };
+        let node_count = reader.read_u32::<LittleEndian>()? as usize;
+
+        let mut nodes = Vec::with_capacity(node_count);
+        for _ in 0..node_count {
+            let mut id_bytes = [0u8; 16];
+            reader.read_exact(&mut id_bytes)?;
+            let id = Uuid::from_bytes(id_bytes);
+
+            let level = reader.read_u32::<LittleEndian>()? as usize;
+            let active = reader.read_u8()? != 0;
+
+            let node_dim = reader.read_u32::<LittleEndian>()? as usize;
+            if node_dim != dim {
+                bail!(
+                    "HNSW index file declares dimension {dim} but node {id} stores {node_dim}"
+                );
+            }
+            let mut data = Vec::with_capacity(dim);
+            for _ in 0..dim {
+                data.push(reader.read_f32::<LittleEndian>()?);
+            }
+
+            let mut neighbours = Vec::with_capacity(level + 1);
+            for _ in 0..=level {
+                let count = reader.read_u32::<LittleEndian>()? as usize;
+                let mut level_neighbours = Vec::with_capacity(count);
+                for _ in 0..count {
+                    level_neighbours.push(reader.read_u32::<LittleEndian>()? as usize);
+                }
+                neighbours.push(level_neighbours);
+            }
+
+            nodes.push(HNSWNode {
+                id,
+                vector: Array1::from(data),
+                level,
+                neighbours,
+                active,
+            });
+        }
+
+        Ok(Self {
+            m,
+            ef,
+            metric,
+            nodes,
+            entry,
+            max_level,
+        })
+    }
 }
 
 impl Index for HNSWIndex {
@@ -312,41 +1419,53 @@ impl Index for HNSWIndex {
     }
 
     fn query(&self, query: &Array1<f32>, top_k: usize) -> Result<Vec<(Uuid, f32)>> {
-        if self.nodes.is_empty() {
+        let Some(entry) = self.entry else {
             return Ok(Vec::new());
-        }
+        };
 
         // Greedy search through upper layers
-        let mut current = self.entry.unwrap();
+        let mut current = entry;
         for level in (1..=self.max_level).rev() {
             current = self.greedy_search(query, current, level);
         }
 
-        // Breadth-first search at level 0
-        let mut visited: HashSet<usize> = HashSet::new();
-        let mut queue: VecDeque<usize> = VecDeque::new();
-        queue.push_back(current);
-        visited.insert(current);
+        // Ef-bounded best-first search at level 0
+        let candidates = self.search_layer(query, current, 0, self.ef);
 
-        while let Some(idx) = queue.pop_front() {
-            for &n in &self.nodes[idx].neighbours[0] {
-                if visited.len() >= self.ef {
-                    break;
-                }
-                if visited.insert(n) {
-                    queue.push_back(n);
-                }
-            }
-            if visited.len() >= self.ef {
-                break;
-            }
+        let mut results: Vec<(Uuid, f32)> = candidates
+            .into_iter()
+            .map(|(idx, _)| {
+                let node = &self.nodes[idx];
+                (node.id, self.metric.score(query, &node.vector))
+            })
+            .collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        results.truncate(top_k);
+        Ok(results)
+    }
+
+    fn query_filtered(
+        &self,
+        query: &Array1<f32>,
+        top_k: usize,
+        predicate: &dyn Fn(&Uuid) -> bool,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let Some(entry) = self.entry else {
+            return Ok(Vec::new());
+        };
+
+        let mut current = entry;
+        for level in (1..=self.max_level).rev() {
+            current = self.greedy_search(query, current, level);
         }
 
-        let mut results: Vec<(Uuid, f32)> = visited
+        let candidates = self.search_layer_filtered(query, current, 0, self.ef, top_k, predicate);
+
+        let mut results: Vec<(Uuid, f32)> = candidates
             .into_iter()
-            .map(|i| {
-                let node = &self.nodes[i];
-                (node.id, cosine_similarity(query, &node.vector))
+            .map(|(idx, _)| {
+                let node = &self.nodes[idx];
+                (node.id, self.metric.score(query, &node.vector))
             })
             .collect();
         results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
@@ -354,6 +1473,103 @@ impl Index for HNSWIndex {
         Ok(results)
     }
 
+    /// Inserts via [`Self::insert_node`], which assigns a fresh random level
+    /// and links the new node into the graph using the same neighbour
+    /// heuristic as `build`.
+    fn insert(&mut self, id: &Uuid, vector: &Array1<f32>) -> Result<()> {
+        if self.nodes.iter().any(|n| n.active && n.id == *id) {
+            return Err(VectorDBError::DuplicateId(*id));
+        }
+        self.insert_node(*id, vector.clone());
+        Ok(())
+    }
+
+    /// Tombstones the node rather than physically removing it (see
+    /// [`HNSWNode::active`]), detaching it from every neighbour's list at
+    /// every level it participated in. The neighbours left behind at each
+    /// level are then cross-linked with each other so removing a node
+    /// doesn't disconnect its former neighbourhood, with
+    /// [`Self::prune_neighbours`] re-applied wherever that backfill pushed a
+    /// list back over `m`. If the removed node was the entry point, a new
+    /// one is picked from the remaining active nodes (or the graph is reset
+    /// if none remain).
+    fn remove(&mut self, id: &Uuid) -> Result<()> {
+        let Some(node_idx) = self.nodes.iter().position(|n| n.active && n.id == *id) else {
+            return Err(VectorDBError::MissingId(*id));
+        };
+
+        for level in 0..=self.nodes[node_idx].level {
+            let affected = self.nodes[node_idx].neighbours[level].clone();
+
+            // Pruning is not symmetric, so nodes outside `affected` can still
+            // hold an edge to `node_idx`; drop it from every list at this level.
+            for n in 0..self.nodes.len() {
+                if self.nodes[n].level >= level {
+                    self.nodes[n].neighbours[level].retain(|&x| x != node_idx);
+                }
+            }
+            self.nodes[node_idx].neighbours[level].clear();
+
+            // Cross-link the orphaned neighbours so the removed node's
+            // former neighbourhood stays connected to itself.
+            for &a in &affected {
+                for &b in &affected {
+                    if a == b || self.nodes[a].neighbours[level].contains(&b) {
+                        continue;
+                    }
+                    self.nodes[a].neighbours[level].push(b);
+                }
+            }
+            for &a in &affected {
+                if self.nodes[a].neighbours[level].len() > self.m {
+                    self.prune_neighbours(a, level);
+                }
+            }
+        }
+
+        self.nodes[node_idx].active = false;
+
+        if self.entry == Some(node_idx) {
+            let new_entry = self
+                .nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| n.active)
+                .max_by_key(|(_, n)| n.level);
+
+            match new_entry {
+                Some((idx, n)) => {
+                    self.entry = Some(idx);
+                    self.max_level = n.level;
+                }
+                None => {
+                    self.entry = None;
+                    self.max_level = 0;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Scans every active node rather than the graph, for the same
+    /// exact-recall reason as [`LSHIndex::query_with_options`].
+    fn query_with_options(
+        &self,
+        query: &Array1<f32>,
+        limit: Option<usize>,
+        threshold: Option<f32>,
+        style: SimilarityStyle,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let results: Vec<(Uuid, f32)> = self
+            .nodes
+            .iter()
+            .filter(|n| n.active)
+            .map(|n| (n.id, style.score(query, &n.vector)))
+            .collect();
+        Ok(rank_with_options(results, limit, threshold, style))
+    }
+
     fn clear(&mut self) {
         self.nodes.clear();
         self.entry = None;
@@ -29,9 +29,11 @@ pub enum VectorDBError {
 pub type Result<T> = std::result::Result<T, VectorDBError>;
 
 // Re-export main types for convenience
-pub use index::{BruteForceIndex, HNSWIndex, Index, LSHIndex};
+pub use index::{
+    BruteForceIndex, DistanceMetric, HNSWIndex, Index, LSHIndex, RPForestIndex, SimilarityStyle,
+};
 pub use persistence::PersistentStorage;
-pub use query::QueryEngine;
+pub use query::{Filter, QueryEngine};
 pub use storage::{InMemoryStorage, Storage};
 pub use utils::{cosine_similarity, euclidean_distance};
 pub use vector::Vector;
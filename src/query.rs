@@ -1,7 +1,68 @@
-use crate::{storage::Storage, index::Index, vector::Vector, Result};
+use crate::{index::{Index, SimilarityStyle}, storage::Storage, vector::Vector, Result};
 use ndarray::Array1;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Default smoothing constant `k` for Reciprocal Rank Fusion in
+/// [`QueryEngine::hybrid_search`]. Dampens the contribution of low ranks so
+/// neither the semantic nor keyword list can dominate just by ranking
+/// something first.
+pub const DEFAULT_RRF_K: f32 = 60.0;
+
+/// A predicate tree over a vector's metadata, for
+/// [`QueryEngine::search_filtered`]. `field` always names a single
+/// top-level key of the metadata object; there's no nested-path lookup.
+/// For a vector with no metadata at all every leaf (`Eq`, `Range`, `In`)
+/// fails to match; `And`, `Or` and `Not` combine those results as usual.
+#[derive(Debug, Clone)]
+pub enum Filter {
+    /// `field` is present and equal to `value`.
+    Eq(String, serde_json::Value),
+    /// `field` is present, numeric, and within `[min, max]` (either bound
+    /// may be omitted to leave that side unconstrained).
+    Range {
+        field: String,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// `field` is present and equal to one of `values`.
+    In(String, Vec<serde_json::Value>),
+    /// Every sub-filter matches.
+    And(Vec<Filter>),
+    /// At least one sub-filter matches.
+    Or(Vec<Filter>),
+    /// The inner filter does not match.
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    /// Evaluates this filter against `metadata`, the metadata of a single
+    /// stored vector.
+    pub fn matches(&self, metadata: &Option<serde_json::Value>) -> bool {
+        match self {
+            Filter::Eq(field, value) => field_value(metadata, field) == Some(value),
+            Filter::Range { field, min, max } => {
+                let Some(n) = field_value(metadata, field).and_then(|v| v.as_f64()) else {
+                    return false;
+                };
+                min.map_or(true, |bound| n >= bound) && max.map_or(true, |bound| n <= bound)
+            }
+            Filter::In(field, values) => field_value(metadata, field)
+                .map(|v| values.iter().any(|candidate| candidate == v))
+                .unwrap_or(false),
+            Filter::And(filters) => filters.iter().all(|f| f.matches(metadata)),
+            Filter::Or(filters) => filters.iter().any(|f| f.matches(metadata)),
+            Filter::Not(inner) => !inner.matches(metadata),
+        }
+    }
+}
+
+/// Looks up `field` as a top-level key of `metadata`, if both the metadata
+/// and the key are present.
+fn field_value<'a>(metadata: &'a Option<serde_json::Value>, field: &str) -> Option<&'a serde_json::Value> {
+    metadata.as_ref()?.get(field)
+}
+
 pub struct QueryEngine<'a> {
     storage: &'a dyn Storage,
     index: &'a dyn Index,
@@ -27,14 +88,70 @@ impl<'a> QueryEngine<'a> {
 
     pub fn search_with_scores(&self, query_vector: &Vector, top_k: usize) -> Result<Vec<(&Vector, f32)>> {
         let results = self.index.query(&query_vector.data, top_k)?;
-        
+
         let mut vectors_with_scores = Vec::new();
         for (id, similarity) in results {
             if let Some(vector) = self.storage.get(&id) {
                 vectors_with_scores.push((vector, similarity));
             }
         }
-        
+
+        Ok(vectors_with_scores)
+    }
+
+    /// Like [`Self::search_with_scores`], but pages through the ranking
+    /// instead of always returning its head: over-fetches `offset + limit`
+    /// results from the index and drops the first `offset`, so repeated
+    /// calls with increasing `offset` walk the full ranked list.
+    pub fn search_paginated(
+        &self,
+        query_vector: &Vector,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<(&Vector, f32)>> {
+        let results = self.search_with_scores(query_vector, offset + limit)?;
+        Ok(results.into_iter().skip(offset).collect())
+    }
+
+    /// Retrieval without a query vector: returns a deterministic, stably
+    /// ordered (by id) page of stored vectors, honoring an optional
+    /// metadata `filter`. Lets callers browse a collection, or page through
+    /// a filtered slice of it, when there's no search term to rank by.
+    pub fn browse(&self, offset: usize, limit: usize, filter: Option<&Filter>) -> Vec<&Vector> {
+        let mut vectors = self.storage.all_vectors();
+        vectors.sort_by_key(|v| v.id);
+
+        vectors
+            .into_iter()
+            .filter(|v| filter.map_or(true, |f| f.matches(&v.metadata)))
+            .skip(offset)
+            .take(limit)
+            .collect()
+    }
+
+    /// Like [`Self::search_with_scores`], but surfaces
+    /// [`Index::query_with_options`]'s optional `limit`/`threshold` and a
+    /// per-call [`SimilarityStyle`] instead of a fixed `top_k` and the
+    /// index's own scoring. `limit = None` returns every match that passes
+    /// `threshold` rather than a capped count.
+    pub fn search_with_options(
+        &self,
+        query_vector: &Vector,
+        limit: Option<usize>,
+        threshold: Option<f32>,
+        style: SimilarityStyle,
+    ) -> Result<Vec<(&Vector, f32)>> {
+        let results = self
+            .index
+            .query_with_options(&query_vector.data, limit, threshold, style)?;
+
+        let mut vectors_with_scores = Vec::new();
+        for (id, similarity) in results {
+            if let Some(vector) = self.storage.get(&id) {
+                vectors_with_scores.push((vector, similarity));
+            }
+        }
+
         Ok(vectors_with_scores)
     }
 
@@ -51,6 +168,43 @@ impl<'a> QueryEngine<'a> {
         Ok(vectors)
     }
 
+    /// Runs `top_k` similarity search for every vector in `queries`,
+    /// returning one scored result list per query in the same order.
+    ///
+    /// Ids frequently repeat across the result lists of nearby queries
+    /// (the same popular neighbor turns up for several queries in a
+    /// batch), so this shares a single `id -> &Vector` cache across the
+    /// whole batch instead of calling `self.storage.get` again for an id
+    /// already resolved for an earlier query. A per-index single-pass
+    /// scoring strategy (rather than re-querying the index per vector)
+    /// would need a batched method on [`Index`] itself, which this does
+    /// not add.
+    pub fn search_batch(&self, queries: &[Vector], top_k: usize) -> Result<Vec<Vec<(&Vector, f32)>>> {
+        let mut resolved: HashMap<Uuid, &Vector> = HashMap::new();
+
+        queries
+            .iter()
+            .map(|query_vector| {
+                let raw = self.index.query(&query_vector.data, top_k)?;
+
+                Ok(raw
+                    .into_iter()
+                    .filter_map(|(id, score)| {
+                        let vector = match resolved.get(&id) {
+                            Some(vector) => *vector,
+                            None => {
+                                let vector = self.storage.get(&id)?;
+                                resolved.insert(id, vector);
+                                vector
+                            }
+                        };
+                        Some((vector, score))
+                    })
+                    .collect())
+            })
+            .collect()
+    }
+
     pub fn get_vector(&self, id: &Uuid) -> Option<&Vector> {
         self.storage.get(id)
     }
@@ -58,4 +212,162 @@ impl<'a> QueryEngine<'a> {
     pub fn count_vectors(&self) -> usize {
         self.storage.count()
     }
-} 
\ No newline at end of file
+
+    /// Like [`Self::search`], but only vectors whose metadata satisfies
+    /// `filter` are eligible. Delegates the over-fetch (keep pulling
+    /// candidates until `top_k` pass, or the index is exhausted) to
+    /// [`Index::query_filtered`], so this never silently under-returns for
+    /// a selective filter the way filtering a plain `top_k` result would.
+    pub fn search_filtered(
+        &self,
+        query_vector: &Vector,
+        top_k: usize,
+        filter: &Filter,
+    ) -> Result<Vec<(&Vector, f32)>> {
+        let storage = self.storage;
+        let predicate = |id: &Uuid| {
+            storage
+                .get(id)
+                .map(|vector| filter.matches(&vector.metadata))
+                .unwrap_or(false)
+        };
+
+        let results = self.index.query_filtered(&query_vector.data, top_k, &predicate)?;
+
+        Ok(results
+            .into_iter()
+            .filter_map(|(id, score)| self.storage.get(&id).map(|vector| (vector, score)))
+            .collect())
+    }
+
+    /// Combines vector similarity with a keyword/lexical ranking over each
+    /// stored vector's metadata using [`DEFAULT_RRF_K`]. See
+    /// [`Self::hybrid_search_with_k`] for how the two lists are fused.
+    pub fn hybrid_search(
+        &self,
+        query_vector: &Vector,
+        query_text: &str,
+        top_k: usize,
+        semantic_weight: f32,
+    ) -> Result<Vec<(&Vector, f32)>> {
+        self.hybrid_search_with_k(query_vector, query_text, top_k, semantic_weight, DEFAULT_RRF_K)
+    }
+
+    /// Same as [`Self::hybrid_search`] but with an explicit RRF `k` instead
+    /// of [`DEFAULT_RRF_K`].
+    ///
+    /// Runs the semantic search via `self.index.query` to get one ranked id
+    /// list, scores every stored vector's metadata against `query_text` by
+    /// shared-term count to get a second ranked id list, then fuses them
+    /// with `fused_score(id) = Σ weight_i / (k + rank_i(id))`, where
+    /// `rank_i` is the 1-based position of `id` in list `i` (ids absent from
+    /// a list contribute nothing for it). `semantic_weight` scales the
+    /// vector list's contribution; the keyword list gets
+    /// `1.0 - semantic_weight`. This avoids having to pre-normalize cosine
+    /// similarity against a keyword score on a totally different scale.
+    pub fn hybrid_search_with_k(
+        &self,
+        query_vector: &Vector,
+        query_text: &str,
+        top_k: usize,
+        semantic_weight: f32,
+        k: f32,
+    ) -> Result<Vec<(&Vector, f32)>> {
+        let semantic_ranked: Vec<Uuid> = self
+            .index
+            .query(&query_vector.data, self.storage.count())?
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+
+        let keyword_ranked = keyword_ranked(self.storage.all_vectors(), query_text);
+
+        let mut fused_scores: HashMap<Uuid, f32> = HashMap::new();
+        for (rank, id) in semantic_ranked.into_iter().enumerate() {
+            *fused_scores.entry(id).or_insert(0.0) += semantic_weight / (k + (rank + 1) as f32);
+        }
+        for (rank, id) in keyword_ranked.into_iter().enumerate() {
+            *fused_scores.entry(id).or_insert(0.0) += (1.0 - semantic_weight) / (k + (rank + 1) as f32);
+        }
+
+        let mut results: Vec<(Uuid, f32)> = fused_scores.into_iter().collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(top_k);
+
+        Ok(results
+            .into_iter()
+            .filter_map(|(id, score)| self.storage.get(&id).map(|vector| (vector, score)))
+            .collect())
+    }
+
+    /// Like [`Self::search_with_scores`], but also tallies, for each field
+    /// in `facet_fields`, how many of the returned hits carry each distinct
+    /// value of that field. Lets a caller render facet filters next to
+    /// results without a second pass over storage.
+    pub fn search_with_facets(
+        &self,
+        query_vector: &Vector,
+        top_k: usize,
+        facet_fields: &[&str],
+    ) -> Result<(Vec<(&Vector, f32)>, HashMap<String, HashMap<String, usize>>)> {
+        let results = self.search_with_scores(query_vector, top_k)?;
+
+        let mut facets: HashMap<String, HashMap<String, usize>> = HashMap::new();
+        for field in facet_fields {
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for (vector, _) in &results {
+                if let Some(value) = field_value(&vector.metadata, field) {
+                    *counts.entry(facet_value_key(value)).or_insert(0) += 1;
+                }
+            }
+            facets.insert((*field).to_string(), counts);
+        }
+
+        Ok((results, facets))
+    }
+}
+
+/// Renders a metadata value as a facet bucket key: plain strings are used
+/// as-is (so a `category: "books"` facet reads "books", not `"\"books\""`),
+/// everything else falls back to its JSON representation.
+fn facet_value_key(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Ranks `vectors` by how many lowercased, alphanumeric-split tokens their
+/// flattened metadata shares with `query_text`, descending. Vectors with no
+/// metadata or no shared tokens are dropped rather than padding out the tail
+/// of the list with zero scores.
+fn keyword_ranked(vectors: Vec<&Vector>, query_text: &str) -> Vec<Uuid> {
+    let query_terms = tokenize(query_text);
+
+    let mut scored: Vec<(Uuid, usize)> = vectors
+        .into_iter()
+        .filter_map(|vector| {
+            let metadata = vector.metadata.as_ref()?;
+            let doc_terms = tokenize(&metadata.to_string());
+            let score = query_terms.iter().filter(|term| doc_terms.contains(term)).count();
+            if score > 0 {
+                Some((vector.id, score))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.into_iter().map(|(id, _)| id).collect()
+}
+
+/// Lowercases `text` and splits it on non-alphanumeric characters, dropping
+/// empty tokens.
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
\ No newline at end of file
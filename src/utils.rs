@@ -0,0 +1,33 @@
+use ndarray::Array1;
+
+pub fn cosine_similarity(v1: &Array1<f32>, v2: &Array1<f32>) -> f32 {
+    let dot_product = v1.dot(v2);
+    let norm1 = v1.dot(v1).sqrt();
+    let norm2 = v2.dot(v2).sqrt();
+
+    if norm1 == 0.0 || norm2 == 0.0 {
+        0.0
+    } else {
+        dot_product / (norm1 * norm2)
+    }
+}
+
+pub fn euclidean_distance(v1: &Array1<f32>, v2: &Array1<f32>) -> f32 {
+    let diff = v1 - v2;
+    diff.dot(&diff).sqrt()
+}
+
+pub fn generate_random_vectors(dim: usize, num: usize) -> Vec<Array1<f32>> {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+
+    (0..num)
+        .map(|_| {
+            Array1::from_vec(
+                (0..dim)
+                    .map(|_| rng.gen_range(-1.0..1.0))
+                    .collect()
+            )
+        })
+        .collect()
+}
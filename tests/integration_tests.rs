@@ -1,8 +1,8 @@
 use ndarray::Array1;
 use vector_db::{
-    index::{BruteForceIndex, HNSWIndex, Index, LSHIndex},
+    index::{BruteForceIndex, DistanceMetric, HNSWIndex, Index, LSHIndex, RPForestIndex, SimilarityStyle},
     persistence::PersistentStorage,
-    query::QueryEngine,
+    query::{Filter, QueryEngine},
     storage::{InMemoryStorage, Storage},
     utils::{cosine_similarity, euclidean_distance, generate_random_vectors},
     vector::Vector,
@@ -214,6 +214,301 @@ fn test_query_engine_with_metadata() {
     assert!(first_result.metadata.is_some());
 }
 
+#[test]
+fn test_hybrid_search_fuses_semantic_and_keyword_rankings() {
+    let mut storage = InMemoryStorage::new();
+    let mut index = BruteForceIndex::new();
+
+    // `far_but_tagged` is a poor vector match but the only one whose
+    // metadata mentions "rust", so a keyword-only search would put it
+    // first while a vector-only search would put it last.
+    let close_vector = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+    let far_but_tagged = Vector::with_metadata(
+        Array1::from_vec(vec![0.0, 1.0, 0.0]),
+        serde_json::json!({"tags": "rust programming"}),
+    );
+    let far_id = far_but_tagged.id;
+
+    storage.insert(close_vector.clone()).unwrap();
+    storage.insert(far_but_tagged).unwrap();
+
+    let indexed_data: Vec<_> = storage
+        .all_vectors()
+        .iter()
+        .map(|v| (&v.id, &v.data))
+        .collect();
+    index.build(&indexed_data).unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+
+    // Pure vector search ranks `close_vector` first.
+    let vector_only = query_engine.search(&query, 2).unwrap();
+    assert_eq!(vector_only[0].id, close_vector.id);
+
+    // With most of the weight on the keyword list, the tagged-but-distant
+    // vector should win the fused ranking instead.
+    let results = query_engine
+        .hybrid_search(&query, "rust", 2, 0.1)
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0.id, far_id);
+}
+
+#[test]
+fn test_search_filtered_skips_rejected_metadata_and_keeps_over_fetching() {
+    let mut storage = InMemoryStorage::new();
+    let mut index = BruteForceIndex::new();
+
+    // The nearest neighbor to the query is a wrong_category vector; the
+    // other two tie behind it. A naive top-1-then-filter would
+    // return nothing; search_filtered must keep looking.
+    let wrong_category_near = Vector::with_metadata(
+        Array1::from_vec(vec![1.0, 0.0, 0.0]),
+        serde_json::json!({"category": "videos"}),
+    );
+    let right_category_far = Vector::with_metadata(
+        Array1::from_vec(vec![0.0, 1.0, 0.0]),
+        serde_json::json!({"category": "books"}),
+    );
+    let right_id = right_category_far.id;
+    let wrong_category_farther = Vector::with_metadata(
+        Array1::from_vec(vec![0.0, 0.0, 1.0]),
+        serde_json::json!({"category": "videos"}),
+    );
+
+    storage.insert(wrong_category_near).unwrap();
+    storage.insert(right_category_far).unwrap();
+    storage.insert(wrong_category_farther).unwrap();
+
+    let indexed_data: Vec<_> = storage
+        .all_vectors()
+        .iter()
+        .map(|v| (&v.id, &v.data))
+        .collect();
+    index.build(&indexed_data).unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+    let filter = Filter::Eq("category".to_string(), serde_json::json!("books"));
+
+    let results = query_engine.search_filtered(&query, 1, &filter).unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.id, right_id);
+}
+
+#[test]
+fn test_filter_and_or_not_combinators() {
+    let metadata = Some(serde_json::json!({"category": "books", "price": 12.5}));
+
+    let cheap_book = Filter::And(vec![
+        Filter::Eq("category".to_string(), serde_json::json!("books")),
+        Filter::Range { field: "price".to_string(), min: None, max: Some(20.0) },
+    ]);
+    assert!(cheap_book.matches(&metadata));
+
+    let expensive_book = Filter::And(vec![
+        Filter::Eq("category".to_string(), serde_json::json!("books")),
+        Filter::Range { field: "price".to_string(), min: Some(100.0), max: None },
+    ]);
+    assert!(!expensive_book.matches(&metadata));
+
+    let books_or_videos = Filter::Or(vec![
+        Filter::Eq("category".to_string(), serde_json::json!("videos")),
+        Filter::In(
+            "category".to_string(),
+            vec![serde_json::json!("books"), serde_json::json!("music")],
+        ),
+    ]);
+    assert!(books_or_videos.matches(&metadata));
+
+    let not_a_video = Filter::Not(Box::new(Filter::Eq(
+        "category".to_string(),
+        serde_json::json!("videos"),
+    )));
+    assert!(not_a_video.matches(&metadata));
+
+    assert!(!cheap_book.matches(&None));
+}
+
+#[test]
+fn test_search_with_facets_tallies_metadata_values_across_results() {
+    let mut storage = InMemoryStorage::new();
+    let mut index = BruteForceIndex::new();
+
+    let book_a = Vector::with_metadata(
+        Array1::from_vec(vec![1.0, 0.0, 0.0]),
+        serde_json::json!({"category": "books", "in_stock": true}),
+    );
+    let book_b = Vector::with_metadata(
+        Array1::from_vec(vec![0.9, 0.1, 0.0]),
+        serde_json::json!({"category": "books", "in_stock": false}),
+    );
+    let video = Vector::with_metadata(
+        Array1::from_vec(vec![0.8, 0.2, 0.0]),
+        serde_json::json!({"category": "videos", "in_stock": true}),
+    );
+
+    storage.insert(book_a).unwrap();
+    storage.insert(book_b).unwrap();
+    storage.insert(video).unwrap();
+
+    let indexed_data: Vec<_> = storage
+        .all_vectors()
+        .iter()
+        .map(|v| (&v.id, &v.data))
+        .collect();
+    index.build(&indexed_data).unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+
+    let (results, facets) = query_engine
+        .search_with_facets(&query, 3, &["category", "in_stock"])
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+
+    let category_facet = &facets["category"];
+    assert_eq!(category_facet["books"], 2);
+    assert_eq!(category_facet["videos"], 1);
+
+    let in_stock_facet = &facets["in_stock"];
+    assert_eq!(in_stock_facet["true"], 2);
+    assert_eq!(in_stock_facet["false"], 1);
+}
+
+#[test]
+fn test_search_paginated_walks_the_full_ranking_without_overlap() {
+    let mut storage = InMemoryStorage::new();
+    let mut index = BruteForceIndex::new();
+
+    let vectors: Vec<Vector> = vec![
+        Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0])),
+        Vector::new(Array1::from_vec(vec![0.9, 0.1, 0.0])),
+        Vector::new(Array1::from_vec(vec![0.8, 0.2, 0.0])),
+        Vector::new(Array1::from_vec(vec![0.7, 0.3, 0.0])),
+    ];
+    for v in &vectors {
+        storage.insert(v.clone()).unwrap();
+    }
+
+    let indexed_data: Vec<_> = storage
+        .all_vectors()
+        .iter()
+        .map(|v| (&v.id, &v.data))
+        .collect();
+    index.build(&indexed_data).unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+
+    let page1 = query_engine.search_paginated(&query, 0, 2).unwrap();
+    let page2 = query_engine.search_paginated(&query, 2, 2).unwrap();
+
+    assert_eq!(page1.len(), 2);
+    assert_eq!(page2.len(), 2);
+    let page1_ids: Vec<_> = page1.iter().map(|(v, _)| v.id).collect();
+    let page2_ids: Vec<_> = page2.iter().map(|(v, _)| v.id).collect();
+    assert!(page1_ids.iter().all(|id| !page2_ids.contains(id)));
+
+    let unpaginated = query_engine.search_with_scores(&query, 4).unwrap();
+    let expected_ids: Vec<_> = unpaginated.iter().map(|(v, _)| v.id).collect();
+    assert_eq!(page1_ids, expected_ids[..2]);
+    assert_eq!(page2_ids, expected_ids[2..]);
+}
+
+#[test]
+fn test_browse_pages_through_a_deterministic_filtered_order_without_a_query() {
+    let mut storage = InMemoryStorage::new();
+    let index = BruteForceIndex::new();
+
+    let books: Vec<Vector> = (0..3)
+        .map(|i| {
+            Vector::with_metadata(
+                Array1::from_vec(vec![i as f32, 0.0, 0.0]),
+                serde_json::json!({"category": "books"}),
+            )
+        })
+        .collect();
+    let video = Vector::with_metadata(
+        Array1::from_vec(vec![9.0, 0.0, 0.0]),
+        serde_json::json!({"category": "videos"}),
+    );
+
+    for b in &books {
+        storage.insert(b.clone()).unwrap();
+    }
+    storage.insert(video).unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let filter = Filter::Eq("category".to_string(), serde_json::json!("books"));
+
+    let all_books = query_engine.browse(0, 10, Some(&filter));
+    assert_eq!(all_books.len(), 3);
+
+    let first_page = query_engine.browse(0, 2, Some(&filter));
+    let second_page = query_engine.browse(2, 2, Some(&filter));
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(second_page.len(), 1);
+
+    // Paging must be stable: the two pages together reproduce the
+    // unpaginated order with no overlap or gaps.
+    let mut combined: Vec<_> = first_page.iter().chain(second_page.iter()).map(|v| v.id).collect();
+    let mut expected: Vec<_> = all_books.iter().map(|v| v.id).collect();
+    combined.sort();
+    expected.sort();
+    assert_eq!(combined, expected);
+
+    let unfiltered = query_engine.browse(0, 10, None);
+    assert_eq!(unfiltered.len(), 4);
+}
+
+#[test]
+fn test_search_batch_returns_one_result_list_per_query_in_order() {
+    let mut storage = InMemoryStorage::new();
+    let mut index = BruteForceIndex::new();
+
+    let a = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+    let b = Vector::new(Array1::from_vec(vec![0.0, 1.0, 0.0]));
+    let c = Vector::new(Array1::from_vec(vec![0.0, 0.0, 1.0]));
+    let a_id = a.id;
+    let b_id = b.id;
+
+    storage.insert(a).unwrap();
+    storage.insert(b).unwrap();
+    storage.insert(c).unwrap();
+
+    let indexed_data: Vec<_> = storage
+        .all_vectors()
+        .iter()
+        .map(|v| (&v.id, &v.data))
+        .collect();
+    index.build(&indexed_data).unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let query_a = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+    let query_b = Vector::new(Array1::from_vec(vec![0.0, 1.0, 0.0]));
+
+    let batch_results = query_engine
+        .search_batch(&[query_a.clone(), query_b.clone()], 1)
+        .unwrap();
+
+    assert_eq!(batch_results.len(), 2);
+    assert_eq!(batch_results[0].len(), 1);
+    assert_eq!(batch_results[0][0].0.id, a_id);
+    assert_eq!(batch_results[1].len(), 1);
+    assert_eq!(batch_results[1][0].0.id, b_id);
+
+    // Matches running each query individually through search_with_scores.
+    let individual_a = query_engine.search_with_scores(&query_a, 1).unwrap();
+    let individual_b = query_engine.search_with_scores(&query_b, 1).unwrap();
+    assert_eq!(batch_results[0][0].0.id, individual_a[0].0.id);
+    assert_eq!(batch_results[1][0].0.id, individual_b[0].0.id);
+}
+
 #[test]
 fn test_lsh_index_query() {
     let vectors_data = generate_random_vectors(16, 20);
@@ -234,7 +529,7 @@ fn test_lsh_index_query() {
         .map(|v| (&v.id, &v.data))
         .collect();
 
-    let mut index = LSHIndex::new(8);
+    let mut index = LSHIndex::new(8, 8);
     index.build(&indexed_data).unwrap();
 
     let query = first_vector.unwrap();
@@ -271,3 +566,322 @@ fn test_hnsw_index_query() {
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].0, query.id);
 }
+
+#[test]
+fn test_brute_force_index_with_l2_metric_orders_by_euclidean_distance() {
+    let close = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let far = Vector::new(Array1::from_vec(vec![10.0, 10.0]));
+
+    let mut index = BruteForceIndex::with_metric(DistanceMetric::L2);
+    index
+        .build(&[(&close.id, &close.data), (&far.id, &far.data)])
+        .unwrap();
+
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let results = index.query(&query.data, 2).unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, close.id);
+}
+
+#[test]
+fn test_hnsw_index_with_inner_product_metric_still_finds_exact_match() {
+    let vectors_data = generate_random_vectors(16, 20);
+    let mut first_vector: Option<Vector> = None;
+    let vectors: Vec<Vector> = vectors_data
+        .into_iter()
+        .enumerate()
+        .map(|(i, data)| {
+            let v = Vector::new(data);
+            if i == 0 {
+                first_vector = Some(v.clone());
+            }
+            v
+        })
+        .collect();
+
+    let indexed_data: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+
+    let mut index = HNSWIndex::with_metric(8, 16, DistanceMetric::InnerProduct);
+    index.build(&indexed_data).unwrap();
+
+    let query = first_vector.unwrap();
+    let results = index.query(&query.data, 1).unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, query.id);
+}
+
+#[test]
+fn test_brute_force_index_insert_and_remove() {
+    let a = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+    let b = Vector::new(Array1::from_vec(vec![0.0, 1.0, 0.0]));
+
+    let mut index = BruteForceIndex::new();
+    index.insert(&a.id, &a.data).unwrap();
+    index.insert(&b.id, &b.data).unwrap();
+
+    let err = index.insert(&a.id, &a.data).unwrap_err();
+    assert!(matches!(err, VectorDBError::DuplicateId(_)));
+
+    let results = index.query(&a.data, 2).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, a.id);
+
+    index.remove(&a.id).unwrap();
+    let err = index.remove(&a.id).unwrap_err();
+    assert!(matches!(err, VectorDBError::MissingId(_)));
+
+    let results = index.query(&a.data, 2).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, b.id);
+}
+
+#[test]
+fn test_hnsw_index_remove_entry_point_repeatedly_never_panics_on_query() {
+    // Regression test: removing the entry point must re-pick the active
+    // node with the *highest* level (and set max_level to that node's own
+    // level), not just the first active node found. Picking a low-level
+    // node as entry while leaving max_level higher makes the next query
+    // descend into neighbour levels the entry was never connected at,
+    // panicking with an out-of-bounds index. Random levels mean this isn't
+    // deterministically reproducible through the public API, so this
+    // removes every node one at a time (always re-querying in between) to
+    // make hitting that mismatch overwhelmingly likely across runs.
+    let vectors_data = generate_random_vectors(16, 40);
+    let vectors: Vec<Vector> = vectors_data.into_iter().map(Vector::new).collect();
+
+    let indexed_data: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+    let mut index = HNSWIndex::new(8, 16);
+    index.build(&indexed_data).unwrap();
+
+    let probe = Array1::from_vec(vec![0.5; 16]);
+
+    for vector in &vectors {
+        index.remove(&vector.id).unwrap();
+        index.query(&probe, 3).unwrap();
+    }
+}
+
+#[test]
+fn test_hnsw_index_remove_reselects_entry_and_preserves_connectivity() {
+    let vectors_data = generate_random_vectors(16, 15);
+    let vectors: Vec<Vector> = vectors_data.into_iter().map(Vector::new).collect();
+
+    let indexed_data: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+    let mut index = HNSWIndex::new(8, 16);
+    index.build(&indexed_data).unwrap();
+
+    let target = vectors[0].clone();
+    index.remove(&target.id).unwrap();
+
+    let err = index.remove(&target.id).unwrap_err();
+    assert!(matches!(err, VectorDBError::MissingId(_)));
+
+    let results = index.query(&target.data, 5).unwrap();
+    assert!(!results.iter().any(|(id, _)| *id == target.id));
+
+    // The rest of the graph should still be fully searchable.
+    let other = vectors[1].clone();
+    let results = index.query(&other.data, 1).unwrap();
+    assert_eq!(results[0].0, other.id);
+
+    let new_id = uuid::Uuid::new_v4();
+    let new_vector = Array1::from_vec(vec![0.5; 16]);
+    index.insert(&new_id, &new_vector).unwrap();
+    let results = index.query(&new_vector, 1).unwrap();
+    assert_eq!(results[0].0, new_id);
+}
+
+#[test]
+fn test_brute_force_query_filtered_excludes_rejected_ids() {
+    let a = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let b = Vector::new(Array1::from_vec(vec![0.9, 0.1]));
+    let c = Vector::new(Array1::from_vec(vec![0.0, 1.0]));
+
+    let mut index = BruteForceIndex::new();
+    index
+        .build(&[(&a.id, &a.data), (&b.id, &b.data), (&c.id, &c.data)])
+        .unwrap();
+
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let excluded = a.id;
+    let results = index
+        .query_filtered(&query.data, 2, &|id| *id != excluded)
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|(id, _)| *id != excluded));
+    assert_eq!(results[0].0, b.id);
+}
+
+#[test]
+fn test_hnsw_query_filtered_finds_top_k_passing_results() {
+    let vectors_data = generate_random_vectors(16, 30);
+    let vectors: Vec<Vector> = vectors_data.into_iter().map(Vector::new).collect();
+
+    let indexed_data: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+    let mut index = HNSWIndex::new(8, 4);
+    index.build(&indexed_data).unwrap();
+
+    let query = vectors[0].clone();
+    let allowed_ids: std::collections::HashSet<_> = vectors.iter().skip(20).map(|v| v.id).collect();
+
+    let results = index
+        .query_filtered(&query.data, 5, &|id| allowed_ids.contains(id))
+        .unwrap();
+
+    assert_eq!(results.len(), 5);
+    assert!(results.iter().all(|(id, _)| allowed_ids.contains(id)));
+}
+
+#[test]
+fn test_hnsw_index_save_and_load_round_trips_query_results() {
+    let vectors_data = generate_random_vectors(16, 20);
+    let vectors: Vec<Vector> = vectors_data.into_iter().map(Vector::new).collect();
+
+    let indexed_data: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+    let mut index = HNSWIndex::new(8, 16);
+    index.build(&indexed_data).unwrap();
+
+    let query = vectors[0].clone();
+    let expected = index.query(&query.data, 5).unwrap();
+
+    let path = std::env::temp_dir().join(format!("test_hnsw_index_{}.bin", query.id));
+    index.save(&path).unwrap();
+    let loaded = HNSWIndex::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let actual = loaded.query(&query.data, 5).unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_hnsw_index_load_rejects_bad_magic_number() {
+    let path = std::env::temp_dir().join("test_hnsw_index_bad_magic.bin");
+    std::fs::write(&path, b"not an index file").unwrap();
+
+    let err = HNSWIndex::load(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(err, VectorDBError::Other(_)));
+}
+
+#[test]
+fn test_lsh_index_save_and_load_round_trips_query_results() {
+    let vectors_data = generate_random_vectors(16, 20);
+    let vectors: Vec<Vector> = vectors_data.into_iter().map(Vector::new).collect();
+
+    let indexed_data: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+    let mut index = LSHIndex::new(8, 8);
+    index.build(&indexed_data).unwrap();
+
+    let query = vectors[0].clone();
+    let expected = index.query(&query.data, 5).unwrap();
+
+    let path = std::env::temp_dir().join(format!("test_lsh_index_{}.bin", query.id));
+    index.save(&path).unwrap();
+    let loaded = LSHIndex::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let actual = loaded.query(&query.data, 5).unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn test_rp_forest_index_query() {
+    let vectors_data = generate_random_vectors(16, 20);
+    let mut first_vector: Option<Vector> = None;
+    let vectors: Vec<Vector> = vectors_data
+        .into_iter()
+        .enumerate()
+        .map(|(i, data)| {
+            let v = Vector::new(data);
+            if i == 0 {
+                first_vector = Some(v.clone());
+            }
+            v
+        })
+        .collect();
+
+    let indexed_data: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+
+    let mut index = RPForestIndex::new(8, 4);
+    index.build(&indexed_data).unwrap();
+
+    let query = first_vector.unwrap();
+    let results = index.query(&query.data, 5).unwrap();
+
+    assert!(!results.is_empty());
+    assert_eq!(results[0].0, query.id);
+}
+
+
+#[test]
+fn test_search_with_options_unlimited_threshold_returns_all_qualifying_matches() {
+    let mut storage = InMemoryStorage::new();
+    let mut index = BruteForceIndex::new();
+
+    let target = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+    let close = Vector::new(Array1::from_vec(vec![0.9, 0.1, 0.0]));
+    let far = Vector::new(Array1::from_vec(vec![0.0, 1.0, 0.0]));
+
+    storage.insert(target.clone()).unwrap();
+    storage.insert(close).unwrap();
+    storage.insert(far).unwrap();
+
+    let indexed_data: Vec<_> = storage
+        .all_vectors()
+        .iter()
+        .map(|v| (&v.id, &v.data))
+        .collect();
+    index.build(&indexed_data).unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let results = query_engine
+        .search_with_options(&target, None, Some(0.5), SimilarityStyle::Cosine)
+        .unwrap();
+
+    // Only the target and the near-parallel vector should clear the threshold,
+    // regardless of there being no `limit` cap.
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|(_, score)| *score >= 0.5));
+}
+
+#[test]
+fn test_search_with_options_euclidean_distance_orders_ascending_and_respects_threshold() {
+    let mut storage = InMemoryStorage::new();
+    let mut index = BruteForceIndex::new();
+
+    let target = Vector::new(Array1::from_vec(vec![0.0, 0.0, 0.0]));
+    let near = Vector::new(Array1::from_vec(vec![1.0, 0.0, 0.0]));
+    let near_id = near.id;
+    let far = Vector::new(Array1::from_vec(vec![10.0, 0.0, 0.0]));
+
+    storage.insert(target.clone()).unwrap();
+    storage.insert(near).unwrap();
+    storage.insert(far).unwrap();
+
+    let indexed_data: Vec<_> = storage
+        .all_vectors()
+        .iter()
+        .map(|v| (&v.id, &v.data))
+        .collect();
+    index.build(&indexed_data).unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let results = query_engine
+        .search_with_options(
+            &target,
+            None,
+            Some(5.0),
+            SimilarityStyle::EuclideanDistance,
+        )
+        .unwrap();
+
+    // The distant vector exceeds the threshold and must be excluded; the
+    // remaining matches are ordered nearest-first (ascending distance).
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0.id, target.id);
+    assert_eq!(results[1].0.id, near_id);
+    assert!(results[0].1 <= results[1].1);
+}
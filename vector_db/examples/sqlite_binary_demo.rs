@@ -104,7 +104,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n⚙️ System Information:");
     let system_keys = ["created_at", "updated_at", "dimension", "vector_count"];
     for key in &system_keys {
-        if let Some(value) = manager.get_collection(collection_name)?.unwrap().sqlite_storage.get_system_info(key)? {
+        if let Some(value) = manager.get_system_info(collection_name, key)? {
             println!("  {}: {}", key, value);
         }
     }
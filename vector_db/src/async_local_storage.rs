@@ -0,0 +1,264 @@
+use crate::local_storage::{HEADER_SIZE, KWI_MAGIC, KWI_VERSION};
+use crate::{vector::Vector, Result, VectorDBError};
+use ndarray::Array1;
+use std::path::{Path, PathBuf};
+use std::io::SeekFrom;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Async counterpart to [`LocalStorage`](crate::local_storage::LocalStorage),
+/// built on `tokio::fs` instead of blocking `std::fs` so it can be awaited
+/// from inside an async server without stalling the executor thread.
+///
+/// Writes the same magic, version number and header layout as
+/// `LocalStorage`, but its records carry no trailing CRC32 checksum, so
+/// neither type can read records written by the other. It also stays at a
+/// simpler feature baseline: no offset index, embedder integration or
+/// legacy-version migration. Reads and
+/// deletes are a linear scan over the file; callers needing the offset-index
+/// fast path should reach for the sync `LocalStorage` instead.
+///
+/// Takes `&self` (not `&mut self`) on every method so it can be shared across
+/// tasks behind an `Arc`. A single internal mutex guards the header's vector
+/// count and the append position, so concurrent writers can't interleave and
+/// corrupt either.
+pub struct AsyncLocalStorage {
+    vectors_file: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl std::fmt::Debug for AsyncLocalStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncLocalStorage")
+            .field("vectors_file", &self.vectors_file)
+            .finish()
+    }
+}
+
+impl AsyncLocalStorage {
+    /// Opens the KWI file at `path`, creating it with an empty header if it
+    /// doesn't already exist.
+    pub async fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let vectors_file = path.as_ref().to_path_buf();
+
+        if !vectors_file.exists() {
+            Self::create_vectors_file(&vectors_file).await?;
+        }
+
+        Ok(Self {
+            vectors_file,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    async fn create_vectors_file(path: &Path) -> Result<()> {
+        tracing::debug!(?path, "creating KWI vectors file");
+
+        let mut file = File::create(path).await?;
+        file.write_all(KWI_MAGIC).await?;
+        file.write_all(&KWI_VERSION.to_le_bytes()).await?;
+        file.write_all(&0u64.to_le_bytes()).await?; // vector count
+        file.write_all(&0u32.to_le_bytes()).await?; // deleted count
+        file.flush().await?;
+
+        Ok(())
+    }
+
+    /// Appends `vector` to the file and bumps the header's record count.
+    #[tracing::instrument(skip(self, vector), fields(id = %vector.id))]
+    pub async fn add_vector(&self, vector: &Vector) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.vectors_file)
+            .await?;
+
+        file.seek(SeekFrom::End(0)).await?;
+        Self::write_vector_record(&mut file, vector).await?;
+        Self::increment_vector_count(&mut file).await?;
+
+        tracing::debug!("vector appended");
+        Ok(())
+    }
+
+    async fn write_vector_record(file: &mut File, vector: &Vector) -> Result<()> {
+        file.write_all(&[0u8]).await?; // tombstone flag: live
+
+        let id_str = vector.id.to_string();
+        file.write_all(&(id_str.len() as u32).to_le_bytes()).await?;
+
+        let mut id_bytes = [0u8; 36];
+        id_str.as_bytes().iter().enumerate().take(36).for_each(|(i, &byte)| id_bytes[i] = byte);
+        file.write_all(&id_bytes).await?;
+
+        let data_bytes = bincode::serialize(&vector.data)
+            .map_err(|e| VectorDBError::SerializationError(format!("Failed to serialize vector data: {}", e)))?;
+        file.write_all(&(data_bytes.len() as u32).to_le_bytes()).await?;
+        file.write_all(&data_bytes).await?;
+
+        match &vector.metadata {
+            Some(metadata) => {
+                let metadata_bytes = serde_json::to_string(metadata)
+                    .map_err(|e| VectorDBError::SerializationError(format!("Failed to serialize metadata: {}", e)))?
+                    .into_bytes();
+                file.write_all(&(metadata_bytes.len() as u32).to_le_bytes()).await?;
+                file.write_all(&metadata_bytes).await?;
+            }
+            None => {
+                file.write_all(&0u32.to_le_bytes()).await?;
+            }
+        }
+
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn increment_vector_count(file: &mut File) -> Result<()> {
+        file.seek(SeekFrom::Start(8)).await?;
+        let current = file.read_u64_le().await?;
+
+        file.seek(SeekFrom::Start(8)).await?;
+        file.write_all(&(current + 1).to_le_bytes()).await?;
+        file.flush().await?;
+
+        Ok(())
+    }
+
+    /// Scans the file for a live record matching `id`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_vector(&self, id: &Uuid) -> Result<Option<Vector>> {
+        let mut file = File::open(&self.vectors_file).await?;
+
+        if !Self::skip_header(&mut file).await? {
+            return Ok(None);
+        }
+
+        loop {
+            match Self::read_vector_record(&mut file).await? {
+                Some((tombstoned, vector)) if !tombstoned && &vector.id == id => return Ok(Some(vector)),
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Reads every live (non-tombstoned) record in the file.
+    pub async fn get_all_vectors(&self) -> Result<Vec<Vector>> {
+        let mut file = File::open(&self.vectors_file).await?;
+        let mut vectors = Vec::new();
+
+        if !Self::skip_header(&mut file).await? {
+            return Ok(vectors);
+        }
+
+        while let Some((tombstoned, vector)) = Self::read_vector_record(&mut file).await? {
+            if !tombstoned {
+                vectors.push(vector);
+            }
+        }
+
+        Ok(vectors)
+    }
+
+    /// Flips the tombstone flag of `id`'s record in place. A no-op if `id`
+    /// isn't present.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_vector(&self, id: &Uuid) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.vectors_file)
+            .await?;
+
+        if !Self::skip_header(&mut file).await? {
+            return Ok(());
+        }
+
+        loop {
+            let record_start = file.stream_position().await?;
+            match Self::read_vector_record(&mut file).await? {
+                Some((tombstoned, vector)) if !tombstoned && &vector.id == id => {
+                    file.seek(SeekFrom::Start(record_start)).await?;
+                    file.write_all(&[1u8]).await?;
+                    file.flush().await?;
+                    tracing::debug!("vector tombstoned");
+                    return Ok(());
+                }
+                Some(_) => continue,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Number of records ever appended, read from the header. Tombstoned
+    /// records are still counted: `delete_vector` does not lower it, and this
+    /// type never updates the header's deleted-count slot.
+    pub async fn get_vector_count(&self) -> Result<usize> {
+        let mut file = File::open(&self.vectors_file).await?;
+        file.seek(SeekFrom::Start(8)).await?;
+        Ok(file.read_u64_le().await? as usize)
+    }
+
+    /// Seeks `file` past the header, returning `false` if the file holds no
+    /// records at all.
+    async fn skip_header(file: &mut File) -> Result<bool> {
+        let file_size = file.metadata().await?.len();
+        if file_size <= HEADER_SIZE {
+            return Ok(false);
+        }
+
+        file.seek(SeekFrom::Start(HEADER_SIZE)).await?;
+        Ok(true)
+    }
+
+    /// Reads one record at the current position, returning `None` at EOF.
+    async fn read_vector_record(file: &mut File) -> Result<Option<(bool, Vector)>> {
+        let tombstoned = match file.read_u8().await {
+            Ok(flag) => flag != 0,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let _id_len = file.read_u32_le().await?;
+
+        let mut id_bytes = [0u8; 36];
+        file.read_exact(&mut id_bytes).await?;
+        let id_str = std::str::from_utf8(&id_bytes)
+            .map_err(|e| VectorDBError::SerializationError(format!("Failed to parse vector ID: {}", e)))?
+            .trim_matches('\0');
+        let id = Uuid::parse_str(id_str)
+            .map_err(|e| VectorDBError::SerializationError(format!("Failed to parse UUID: {}", e)))?;
+
+        let data_len = file.read_u32_le().await?;
+        let mut data_bytes = vec![0u8; data_len as usize];
+        file.read_exact(&mut data_bytes).await?;
+        let data: Array1<f32> = bincode::deserialize(&data_bytes)
+            .map_err(|e| VectorDBError::SerializationError(format!("Failed to deserialize vector data: {}", e)))?;
+
+        let metadata_len = file.read_u32_le().await?;
+        let metadata = if metadata_len > 0 {
+            let mut metadata_bytes = vec![0u8; metadata_len as usize];
+            file.read_exact(&mut metadata_bytes).await?;
+            let metadata_str = std::str::from_utf8(&metadata_bytes)
+                .map_err(|e| VectorDBError::SerializationError(format!("Failed to parse metadata string: {}", e)))?;
+            Some(
+                serde_json::from_str(metadata_str)
+                    .map_err(|e| VectorDBError::SerializationError(format!("Failed to deserialize metadata: {}", e)))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Some((tombstoned, Vector { id, data, metadata })))
+    }
+
+    pub fn get_storage_path(&self) -> &Path {
+        &self.vectors_file
+    }
+}
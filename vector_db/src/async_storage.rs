@@ -0,0 +1,247 @@
+use crate::{vector::Vector, Result, VectorDBError};
+use redb::{Database, ReadableTable, TableDefinition};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Async counterpart to the sync [`crate::storage::Storage`] trait, for
+/// backends whose operations may legitimately block on I/O (disk, network)
+/// and shouldn't stall the calling task while they do. Every method takes
+/// `&self`, not `&mut self`, so implementations manage their own interior
+/// mutability the way
+/// [`AsyncLocalStorage`](crate::async_local_storage::AsyncLocalStorage)
+/// does, letting callers share one instance across tasks behind an `Arc`.
+pub trait AsyncStorage {
+    async fn insert(&self, vector: Vector) -> Result<()>;
+    async fn get(&self, id: &Uuid) -> Result<Option<Vector>>;
+    async fn delete(&self, id: &Uuid) -> Result<()>;
+    async fn all_vectors(&self) -> Result<Vec<Vector>>;
+    async fn count(&self) -> Result<usize>;
+}
+
+/// In-memory [`AsyncStorage`] backed by a single `tokio::sync::RwLock`.
+/// Shares [`RedbStorage`]'s interface, so tests can stand in an
+/// `InMemoryAsyncStorage` wherever production code takes a durable backend.
+#[derive(Default)]
+pub struct InMemoryAsyncStorage {
+    vectors: RwLock<HashMap<Uuid, Vector>>,
+}
+
+impl InMemoryAsyncStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AsyncStorage for InMemoryAsyncStorage {
+    async fn insert(&self, vector: Vector) -> Result<()> {
+        self.vectors.write().await.insert(vector.id, vector);
+        Ok(())
+    }
+
+    async fn get(&self, id: &Uuid) -> Result<Option<Vector>> {
+        Ok(self.vectors.read().await.get(id).cloned())
+    }
+
+    async fn delete(&self, id: &Uuid) -> Result<()> {
+        self.vectors.write().await.remove(id);
+        Ok(())
+    }
+
+    async fn all_vectors(&self) -> Result<Vec<Vector>> {
+        Ok(self.vectors.read().await.values().cloned().collect())
+    }
+
+    async fn count(&self) -> Result<usize> {
+        Ok(self.vectors.read().await.len())
+    }
+}
+
+const VECTORS_TABLE: TableDefinition<&str, &[u8]> = TableDefinition::new("vectors");
+
+/// Embedded, durable [`AsyncStorage`] backend on top of
+/// [redb](https://docs.rs/redb), a pure-Rust single-file key-value store.
+/// Vectors are bincode-serialized and keyed by their UUID's string form.
+///
+/// redb's API is synchronous and mmap-backed, so every operation is
+/// dispatched through `tokio::task::spawn_blocking` to keep it off the
+/// async executor thread; the actual row access lives in a handful of
+/// associated functions (`row_fetch`, `row_put`, `row_rm_single`, `row_scan`,
+/// `row_count`) that only ever touch the `Database` handle, so they can run
+/// on the blocking pool without borrowing `self`.
+pub struct RedbStorage {
+    db: Arc<Database>,
+}
+
+impl RedbStorage {
+    /// Opens (creating if needed) the redb database at `path` and ensures
+    /// the vectors table exists.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let db = Database::create(path)
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open redb database: {}", e)))?;
+
+        let write_txn = db
+            .begin_write()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open redb write transaction: {}", e)))?;
+        {
+            write_txn
+                .open_table(VECTORS_TABLE)
+                .map_err(|e| VectorDBError::PersistenceError(format!("Failed to create vectors table: {}", e)))?;
+        }
+        write_txn
+            .commit()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to commit redb table creation: {}", e)))?;
+
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    fn row_fetch(db: &Database, id: &Uuid) -> Result<Option<Vec<u8>>> {
+        let read_txn = db
+            .begin_read()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open redb read transaction: {}", e)))?;
+        let table = read_txn
+            .open_table(VECTORS_TABLE)
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open vectors table: {}", e)))?;
+
+        Ok(table
+            .get(id.to_string().as_str())
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to read row {}: {}", id, e)))?
+            .map(|value| value.value().to_vec()))
+    }
+
+    fn row_put(db: &Database, id: &Uuid, bytes: &[u8]) -> Result<()> {
+        let write_txn = db
+            .begin_write()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open redb write transaction: {}", e)))?;
+        {
+            let mut table = write_txn
+                .open_table(VECTORS_TABLE)
+                .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open vectors table: {}", e)))?;
+            table
+                .insert(id.to_string().as_str(), bytes)
+                .map_err(|e| VectorDBError::PersistenceError(format!("Failed to write row {}: {}", id, e)))?;
+        }
+        write_txn
+            .commit()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to commit row write: {}", e)))?;
+        Ok(())
+    }
+
+    fn row_rm_single(db: &Database, id: &Uuid) -> Result<()> {
+        let write_txn = db
+            .begin_write()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open redb write transaction: {}", e)))?;
+        {
+            let mut table = write_txn
+                .open_table(VECTORS_TABLE)
+                .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open vectors table: {}", e)))?;
+            table
+                .remove(id.to_string().as_str())
+                .map_err(|e| VectorDBError::PersistenceError(format!("Failed to remove row {}: {}", id, e)))?;
+        }
+        write_txn
+            .commit()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to commit row removal: {}", e)))?;
+        Ok(())
+    }
+
+    fn row_scan(db: &Database) -> Result<Vec<Vec<u8>>> {
+        let read_txn = db
+            .begin_read()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open redb read transaction: {}", e)))?;
+        let table = read_txn
+            .open_table(VECTORS_TABLE)
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open vectors table: {}", e)))?;
+
+        let mut rows = Vec::new();
+        let iter = table
+            .iter()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to scan vectors table: {}", e)))?;
+        for entry in iter {
+            let (_key, value) = entry
+                .map_err(|e| VectorDBError::PersistenceError(format!("Failed to read row during scan: {}", e)))?;
+            rows.push(value.value().to_vec());
+        }
+        Ok(rows)
+    }
+
+    fn row_count(db: &Database) -> Result<usize> {
+        let read_txn = db
+            .begin_read()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open redb read transaction: {}", e)))?;
+        let table = read_txn
+            .open_table(VECTORS_TABLE)
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open vectors table: {}", e)))?;
+
+        Ok(table
+            .len()
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to count rows: {}", e)))? as usize)
+    }
+
+    fn join_error(e: tokio::task::JoinError) -> VectorDBError {
+        VectorDBError::PersistenceError(format!("redb background task failed: {}", e))
+    }
+}
+
+impl AsyncStorage for RedbStorage {
+    async fn insert(&self, vector: Vector) -> Result<()> {
+        let db = Arc::clone(&self.db);
+        let id = vector.id;
+        let bytes = bincode::serialize(&vector)
+            .map_err(|e| VectorDBError::SerializationError(format!("Failed to serialize vector: {}", e)))?;
+
+        tokio::task::spawn_blocking(move || Self::row_put(&db, &id, &bytes))
+            .await
+            .map_err(Self::join_error)?
+    }
+
+    async fn get(&self, id: &Uuid) -> Result<Option<Vector>> {
+        let db = Arc::clone(&self.db);
+        let id = *id;
+
+        let bytes = tokio::task::spawn_blocking(move || Self::row_fetch(&db, &id))
+            .await
+            .map_err(Self::join_error)??;
+
+        bytes
+            .map(|bytes| {
+                bincode::deserialize(&bytes)
+                    .map_err(|e| VectorDBError::SerializationError(format!("Failed to deserialize vector: {}", e)))
+            })
+            .transpose()
+    }
+
+    async fn delete(&self, id: &Uuid) -> Result<()> {
+        let db = Arc::clone(&self.db);
+        let id = *id;
+
+        tokio::task::spawn_blocking(move || Self::row_rm_single(&db, &id))
+            .await
+            .map_err(Self::join_error)?
+    }
+
+    async fn all_vectors(&self) -> Result<Vec<Vector>> {
+        let db = Arc::clone(&self.db);
+
+        let rows = tokio::task::spawn_blocking(move || Self::row_scan(&db))
+            .await
+            .map_err(Self::join_error)??;
+
+        rows.into_iter()
+            .map(|bytes| {
+                bincode::deserialize(&bytes)
+                    .map_err(|e| VectorDBError::SerializationError(format!("Failed to deserialize vector: {}", e)))
+            })
+            .collect()
+    }
+
+    async fn count(&self) -> Result<usize> {
+        let db = Arc::clone(&self.db);
+
+        tokio::task::spawn_blocking(move || Self::row_count(&db))
+            .await
+            .map_err(Self::join_error)?
+    }
+}
@@ -0,0 +1,374 @@
+use crate::{
+    binary_index::{BinaryIndex, CompactionStats},
+    persistence::PersistentStorage,
+    sqlite_storage::SQLiteStorage,
+    storage::{InMemoryStorage, Storage},
+    vector::Vector,
+    Result, VectorDBError,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Which [`CollectionBackend`] a collection is stored with, persisted next
+/// to its data so [`crate::collection_manager::CollectionManager::load_collection`]
+/// knows which concrete type to re-open without guessing from whichever
+/// files happen to exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    /// The original SQLite (metadata) + `.kwi` (vectors) hybrid. Supports
+    /// `vacuum`/`flush` for reclaiming space after heavy deletes.
+    SqliteBinary,
+    /// Pure in-memory, not persisted across restarts. Useful for tests and
+    /// ephemeral/throwaway collections.
+    InMemory,
+    /// A single human-inspectable JSON file. Portable at the cost of
+    /// rewriting the whole file on every mutation.
+    Json,
+}
+
+impl BackendKind {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            BackendKind::SqliteBinary => "sqlite_binary",
+            BackendKind::InMemory => "in_memory",
+            BackendKind::Json => "json",
+        }
+    }
+
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s {
+            "sqlite_binary" => Some(BackendKind::SqliteBinary),
+            "in_memory" => Some(BackendKind::InMemory),
+            "json" => Some(BackendKind::Json),
+            _ => None,
+        }
+    }
+}
+
+impl Default for BackendKind {
+    fn default() -> Self {
+        BackendKind::SqliteBinary
+    }
+}
+
+/// Unifies the storage side of a [`crate::collection_manager::Collection`]
+/// behind one interface so `CollectionManager` can mix and match backends
+/// without forking its own code. Implementations are free to store vectors
+/// however they like; `vacuum`/`flush` default to no-ops for backends (like
+/// [`InMemoryBackend`]) that have nothing to reclaim or buffer.
+pub trait CollectionBackend: Send {
+    fn insert(&mut self, vector: &Vector) -> Result<()>;
+    fn get(&self, id: &Uuid) -> Result<Option<Vector>>;
+    fn delete(&mut self, id: &Uuid) -> Result<()>;
+    fn all_vectors(&self) -> Result<Vec<Vector>>;
+    fn count(&self) -> Result<usize>;
+
+    fn set_system_info(&mut self, key: &str, value: &str) -> Result<()>;
+    fn get_system_info(&self, key: &str) -> Result<Option<String>>;
+
+    /// Looks up a vector by content hash (see [`Vector::content_hash`]), for
+    /// dedup on insert. Backends without an indexed hash table fall back to
+    /// scanning `all_vectors()`.
+    fn lookup_by_hash(&self, hash: u64) -> Result<Option<Uuid>> {
+        Ok(self
+            .all_vectors()?
+            .into_iter()
+            .find(|v| v.content_hash() == hash)
+            .map(|v| v.id))
+    }
+
+    /// Records `id`'s content hash so a future `lookup_by_hash` can find it
+    /// without a full scan. Backends that don't override `lookup_by_hash`
+    /// with an indexed table can leave this a no-op.
+    fn record_hash(&mut self, _hash: u64, _id: &Uuid) -> Result<()> {
+        Ok(())
+    }
+
+    /// Applies a batch of inserts and deletes as one unit, for backends that
+    /// can do better than one `insert`/`delete` call at a time. The default
+    /// just loops, respecting content-hash dedup the way `CollectionManager`
+    /// expects; [`SqliteBinaryBackend`] overrides it to tombstone all deletes
+    /// in one SQL transaction and insert all new vectors in a second one. The
+    /// two are not combined into a single transaction, and the content-hash
+    /// rows and the binary index are written outside both.
+    fn apply_batch(&mut self, inserts: &[Vector], deletes: &[Uuid]) -> Result<()> {
+        for id in deletes {
+            self.delete(id)?;
+        }
+        for vector in inserts {
+            let hash = vector.content_hash();
+            if self.lookup_by_hash(hash)?.is_none() {
+                self.insert(vector)?;
+                self.record_hash(hash, &vector.id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reclaims space freed by prior deletes, reporting what it recovered.
+    /// Backends without anything to reclaim just report zero.
+    fn vacuum(&mut self) -> Result<CompactionStats> {
+        Ok(CompactionStats::default())
+    }
+
+    /// Flushes any buffered writes. Backends without buffering are a no-op.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn kind(&self) -> BackendKind;
+}
+
+/// The original hybrid backend: vectors live in a `.kwi` [`BinaryIndex`] for
+/// fast retrieval, while [`SQLiteStorage`] holds metadata and system info
+/// and acts as the durable source of truth deletes fall back to.
+pub struct SqliteBinaryBackend {
+    pub(crate) sqlite_storage: SQLiteStorage,
+    pub(crate) binary_index: BinaryIndex,
+}
+
+impl SqliteBinaryBackend {
+    pub(crate) fn new(db_path: &Path, index_path: &Path, collection_name: &str, dimension: usize) -> Result<Self> {
+        Ok(Self {
+            sqlite_storage: SQLiteStorage::new(db_path, collection_name)?,
+            binary_index: BinaryIndex::new(index_path, dimension)?,
+        })
+    }
+
+    pub(crate) fn open(db_path: &Path, index_path: &Path, collection_name: &str) -> Result<Self> {
+        let sqlite_storage = SQLiteStorage::new(db_path, collection_name)?;
+        // Dimension is whatever the existing `.kwi` file's header already
+        // records; `BinaryIndex::new` on a pre-existing file reopens it
+        // rather than recreating it with this placeholder.
+        let binary_index = BinaryIndex::new(index_path, 128)?;
+        Ok(Self { sqlite_storage, binary_index })
+    }
+}
+
+impl CollectionBackend for SqliteBinaryBackend {
+    fn insert(&mut self, vector: &Vector) -> Result<()> {
+        self.sqlite_storage.insert_vector(vector)?;
+        self.binary_index.add_vector(vector)?;
+        Ok(())
+    }
+
+    fn get(&self, id: &Uuid) -> Result<Option<Vector>> {
+        if let Some(vector) = self.binary_index.get_vector(id)? {
+            return Ok(Some(vector));
+        }
+        self.sqlite_storage.get_vector(id)
+    }
+
+    fn delete(&mut self, id: &Uuid) -> Result<()> {
+        if let Some(vector) = self.sqlite_storage.get_vector(id)? {
+            self.sqlite_storage.remove_content_hash(vector.content_hash())?;
+        }
+        self.sqlite_storage.delete_vector(id)?;
+        self.binary_index.delete_vector(id)?;
+        Ok(())
+    }
+
+    fn all_vectors(&self) -> Result<Vec<Vector>> {
+        self.binary_index.get_all_vectors()
+    }
+
+    fn count(&self) -> Result<usize> {
+        Ok(self.binary_index.count_vectors())
+    }
+
+    fn set_system_info(&mut self, key: &str, value: &str) -> Result<()> {
+        self.sqlite_storage.set_system_info(key, value)
+    }
+
+    fn get_system_info(&self, key: &str) -> Result<Option<String>> {
+        self.sqlite_storage.get_system_info(key)
+    }
+
+    fn lookup_by_hash(&self, hash: u64) -> Result<Option<Uuid>> {
+        self.sqlite_storage.lookup_content_hash(hash)
+    }
+
+    fn record_hash(&mut self, hash: u64, id: &Uuid) -> Result<()> {
+        self.sqlite_storage.record_content_hash(hash, id)
+    }
+
+    fn apply_batch(&mut self, inserts: &[Vector], deletes: &[Uuid]) -> Result<()> {
+        for id in deletes {
+            if let Some(vector) = self.sqlite_storage.get_vector(id)? {
+                self.sqlite_storage.remove_content_hash(vector.content_hash())?;
+            }
+        }
+        self.sqlite_storage.delete_vectors(deletes)?;
+        for id in deletes {
+            self.binary_index.delete_vector(id)?;
+        }
+
+        let mut fresh = Vec::new();
+        for vector in inserts {
+            if self.sqlite_storage.lookup_content_hash(vector.content_hash())?.is_none() {
+                fresh.push(vector.clone());
+            }
+        }
+
+        self.sqlite_storage.insert_vectors(&fresh)?;
+        for vector in &fresh {
+            self.binary_index.add_vector(vector)?;
+            self.sqlite_storage.record_content_hash(vector.content_hash(), &vector.id)?;
+        }
+
+        Ok(())
+    }
+
+    fn vacuum(&mut self) -> Result<CompactionStats> {
+        self.binary_index.compact()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.sqlite_storage.commit()?;
+        self.binary_index.flush()
+    }
+
+    fn kind(&self) -> BackendKind {
+        BackendKind::SqliteBinary
+    }
+}
+
+/// Pure in-memory backend: no files are ever written, so a collection
+/// created with this backend doesn't survive past the process that created
+/// it. Intended for tests and ephemeral/scratch collections.
+#[derive(Default)]
+pub struct InMemoryBackend {
+    storage: InMemoryStorage,
+    system_info: HashMap<String, String>,
+}
+
+impl InMemoryBackend {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CollectionBackend for InMemoryBackend {
+    fn insert(&mut self, vector: &Vector) -> Result<()> {
+        self.storage.insert(vector.clone())
+    }
+
+    fn get(&self, id: &Uuid) -> Result<Option<Vector>> {
+        Ok(self.storage.get(id))
+    }
+
+    fn delete(&mut self, id: &Uuid) -> Result<()> {
+        self.storage.delete(id)
+    }
+
+    fn all_vectors(&self) -> Result<Vec<Vector>> {
+        Ok(self.storage.all_vectors())
+    }
+
+    fn count(&self) -> Result<usize> {
+        Ok(self.storage.count())
+    }
+
+    fn set_system_info(&mut self, key: &str, value: &str) -> Result<()> {
+        self.system_info.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn get_system_info(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.system_info.get(key).cloned())
+    }
+
+    fn kind(&self) -> BackendKind {
+        BackendKind::InMemory
+    }
+}
+
+/// Single-file JSON backend built on [`PersistentStorage`]. Vectors and
+/// system info are both kept in memory and rewritten to their files on every
+/// mutation, the same whole-file-rewrite tradeoff `PersistentStorage` itself
+/// makes, in exchange for a format a user can open and read directly.
+pub struct JsonBackend {
+    storage: PersistentStorage,
+    vectors: HashMap<Uuid, Vector>,
+    system_info: HashMap<String, String>,
+    system_info_path: PathBuf,
+}
+
+impl JsonBackend {
+    pub(crate) fn new(vectors_path: &Path, system_info_path: &Path) -> Result<Self> {
+        let storage = PersistentStorage::new(vectors_path);
+        let vectors = storage
+            .load()?
+            .into_iter()
+            .map(|v| (v.id, v))
+            .collect();
+        let system_info = load_system_info(system_info_path)?;
+
+        Ok(Self {
+            storage,
+            vectors,
+            system_info,
+            system_info_path: system_info_path.to_path_buf(),
+        })
+    }
+
+    fn persist_vectors(&self) -> Result<()> {
+        let all: Vec<Vector> = self.vectors.values().cloned().collect();
+        self.storage.save(&all)
+    }
+
+    fn persist_system_info(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.system_info)
+            .map_err(|e| VectorDBError::SerializationError(format!("Failed to serialize system info: {}", e)))?;
+        std::fs::write(&self.system_info_path, json)
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to write system info: {}", e)))
+    }
+}
+
+fn load_system_info(path: &Path) -> Result<HashMap<String, String>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| VectorDBError::PersistenceError(format!("Failed to read system info: {}", e)))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| VectorDBError::SerializationError(format!("Failed to parse system info: {}", e)))
+}
+
+impl CollectionBackend for JsonBackend {
+    fn insert(&mut self, vector: &Vector) -> Result<()> {
+        self.vectors.insert(vector.id, vector.clone());
+        self.persist_vectors()
+    }
+
+    fn get(&self, id: &Uuid) -> Result<Option<Vector>> {
+        Ok(self.vectors.get(id).cloned())
+    }
+
+    fn delete(&mut self, id: &Uuid) -> Result<()> {
+        self.vectors.remove(id);
+        self.persist_vectors()
+    }
+
+    fn all_vectors(&self) -> Result<Vec<Vector>> {
+        Ok(self.vectors.values().cloned().collect())
+    }
+
+    fn count(&self) -> Result<usize> {
+        Ok(self.vectors.len())
+    }
+
+    fn set_system_info(&mut self, key: &str, value: &str) -> Result<()> {
+        self.system_info.insert(key.to_string(), value.to_string());
+        self.persist_system_info()
+    }
+
+    fn get_system_info(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.system_info.get(key).cloned())
+    }
+
+    fn kind(&self) -> BackendKind {
+        BackendKind::Json
+    }
+}
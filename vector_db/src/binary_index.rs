@@ -1,14 +1,270 @@
 use crate::{vector::Vector, Result};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::fs::{File, OpenOptions};
-use std::io::{Read, Write, Seek, SeekFrom};
+use std::io::{Read, Write, Seek, SeekFrom, BufReader, BufWriter};
 use std::path::Path;
 use uuid::Uuid;
 use ndarray::Array1;
 use std::collections::HashMap;
 
 const KWI_MAGIC: &[u8; 4] = b"KWI\0";
-const KWI_VERSION: u32 = 1;
+const KWI_VERSION: u32 = 5;
+
+/// Fraction of record bytes (live plus dead) that may belong to dead
+/// (tombstoned) records before `add_vector`/`delete_vector` trigger an
+/// automatic `compact()`.
+const DEFAULT_AUTO_COMPACT_THRESHOLD: f64 = 0.3;
+
+/// Default cap on a single segment file's size before `add_vector` rolls
+/// over to a new one.
+const DEFAULT_MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Size in bytes of the Argon2 salt stored in the header.
+const SALT_SIZE: usize = 16;
+/// Size in bytes of the per-record AEAD nonce (96 bits).
+const NONCE_SIZE: usize = 12;
+
+/// Size in bytes of one serialized index entry (see `write_entry_record`):
+/// id, segment, offset, dimension, metadata_size, dead, codec,
+/// uncompressed_size, compressed_size, checksum.
+const ENTRY_RECORD_SIZE: u64 = 16 + 4 + 8 + 4 + 4 + 1 + 1 + 4 + 4 + 8;
+
+/// xxh3_64 of `bytes`, used for both per-record and footer checksums.
+fn checksum(bytes: &[u8]) -> u64 {
+    xxhash_rust::xxh3::xxh3_64(bytes)
+}
+
+/// Which AEAD cipher (if any) protects each record's data+metadata payload,
+/// stored as a 1-byte tag in the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptionType {
+    None,
+    AesGcm,
+    ChaCha20Poly1305,
+}
+
+impl EncryptionType {
+    fn tag(self) -> u8 {
+        match self {
+            EncryptionType::None => 0,
+            EncryptionType::AesGcm => 1,
+            EncryptionType::ChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(EncryptionType::None),
+            1 => Ok(EncryptionType::AesGcm),
+            2 => Ok(EncryptionType::ChaCha20Poly1305),
+            other => Err(crate::VectorDBError::PersistenceError(format!(
+                "Unknown encryption tag: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Key material (and choice of cipher) used to open an encrypted KWI file,
+/// or the absence of either for a plaintext one. Passed to
+/// [`BinaryIndex::new_with_opener`] so that files written without encryption
+/// remain fully backward compatible: an [`BinaryIndexOpener::none`] opener
+/// never touches the header's encryption fields.
+#[derive(Clone)]
+pub struct BinaryIndexOpener {
+    passphrase: Option<(EncryptionType, String)>,
+}
+
+impl std::fmt::Debug for BinaryIndexOpener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BinaryIndexOpener")
+            .field("encryption", &self.passphrase.as_ref().map(|(e, _)| e))
+            .field("passphrase", &self.passphrase.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl BinaryIndexOpener {
+    /// No encryption: the index is read/written as plaintext, as before.
+    pub fn none() -> Self {
+        Self { passphrase: None }
+    }
+
+    /// Derive a 256-bit key from `passphrase` (via Argon2) to encrypt/decrypt
+    /// every record with `encryption`.
+    pub fn with_passphrase(encryption: EncryptionType, passphrase: impl Into<String>) -> Self {
+        Self {
+            passphrase: Some((encryption, passphrase.into())),
+        }
+    }
+
+    fn derive_key(passphrase: &str, salt: &[u8; SALT_SIZE]) -> Result<[u8; 32]> {
+        use argon2::Argon2;
+
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| crate::VectorDBError::IntegrityError(format!("Key derivation failed: {}", e)))?;
+        Ok(key)
+    }
+}
+
+/// Runtime (never persisted) AEAD cipher used to protect record payloads.
+#[derive(Clone)]
+enum Cipher {
+    AesGcm(aes_gcm::Aes256Gcm),
+    ChaCha20Poly1305(chacha20poly1305::ChaCha20Poly1305),
+}
+
+impl Cipher {
+    fn new(encryption: EncryptionType, key: &[u8; 32]) -> Result<Self> {
+        use aead::KeyInit;
+
+        match encryption {
+            EncryptionType::None => Err(crate::VectorDBError::IntegrityError(
+                "Cannot build a cipher for EncryptionType::None".to_string(),
+            )),
+            EncryptionType::AesGcm => Ok(Cipher::AesGcm(aes_gcm::Aes256Gcm::new(key.into()))),
+            EncryptionType::ChaCha20Poly1305 => Ok(Cipher::ChaCha20Poly1305(
+                chacha20poly1305::ChaCha20Poly1305::new(key.into()),
+            )),
+        }
+    }
+
+    fn seal(&self, nonce: &[u8; NONCE_SIZE], plaintext: &[u8]) -> Result<Vec<u8>> {
+        use aead::Aead;
+
+        match self {
+            Cipher::AesGcm(cipher) => cipher.encrypt(nonce.into(), plaintext),
+            Cipher::ChaCha20Poly1305(cipher) => cipher.encrypt(nonce.into(), plaintext),
+        }
+        .map_err(|e| crate::VectorDBError::IntegrityError(format!("Failed to encrypt record: {}", e)))
+    }
+
+    fn open(&self, nonce: &[u8; NONCE_SIZE], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        use aead::Aead;
+
+        match self {
+            Cipher::AesGcm(cipher) => cipher.decrypt(nonce.into(), ciphertext),
+            Cipher::ChaCha20Poly1305(cipher) => cipher.decrypt(nonce.into(), ciphertext),
+        }
+        .map_err(|_| {
+            crate::VectorDBError::IntegrityError(
+                "Failed to authenticate record: wrong passphrase or corrupted data".to_string(),
+            )
+        })
+    }
+}
+
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Cipher(..)")
+    }
+}
+
+/// Per-record compression codec, stored as a 1-byte tag ahead of each
+/// record's payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    None,
+    Lz4,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    fn tag(self) -> u8 {
+        match self {
+            CompressionAlgorithm::None => 0,
+            CompressionAlgorithm::Lz4 => 1,
+            CompressionAlgorithm::Zstd => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(CompressionAlgorithm::None),
+            1 => Ok(CompressionAlgorithm::Lz4),
+            2 => Ok(CompressionAlgorithm::Zstd),
+            other => Err(crate::VectorDBError::PersistenceError(format!(
+                "Unknown compression tag: {}",
+                other
+            ))),
+        }
+    }
+
+    fn compress(self, payload: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            CompressionAlgorithm::None => Ok(payload.to_vec()),
+            CompressionAlgorithm::Lz4 => Ok(lz4_flex::compress(payload)),
+            CompressionAlgorithm::Zstd => zstd::encode_all(payload, 0)
+                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to zstd-compress record: {}", e))),
+        }
+    }
+
+    fn decompress(self, bytes: &[u8], uncompressed_size: usize) -> Result<Vec<u8>> {
+        match self {
+            CompressionAlgorithm::None => Ok(bytes.to_vec()),
+            CompressionAlgorithm::Lz4 => lz4_flex::decompress(bytes, uncompressed_size)
+                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to lz4-decompress record: {}", e))),
+            CompressionAlgorithm::Zstd => zstd::decode_all(bytes)
+                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to zstd-decompress record: {}", e))),
+        }
+    }
+}
+
+/// Tuning knobs for a [`BinaryIndex`]. By default auto-compaction runs at
+/// `DEFAULT_AUTO_COMPACT_THRESHOLD` and records are stored uncompressed.
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryIndexConfig {
+    /// When `dead_bytes / (live_bytes + dead_bytes)` exceeds this ratio, a compaction is run
+    /// automatically after the operation that crossed it. `None` disables
+    /// auto-compaction entirely.
+    pub auto_compact_threshold: Option<f64>,
+    /// Codec applied to each record's data+metadata blob before it is
+    /// written. If the compressed form isn't smaller than the original,
+    /// the record falls back to storing it uncompressed.
+    pub compression: CompressionAlgorithm,
+    /// Records are written to numbered segment files (`vectors.000.kwi`,
+    /// `vectors.001.kwi`, ...) alongside the main index file; once the
+    /// current segment would exceed this many bytes, `add_vector` rolls
+    /// over to the next one.
+    pub max_segment_size: u64,
+}
+
+impl Default for BinaryIndexConfig {
+    fn default() -> Self {
+        Self {
+            auto_compact_threshold: Some(DEFAULT_AUTO_COMPACT_THRESHOLD),
+            compression: CompressionAlgorithm::None,
+            max_segment_size: DEFAULT_MAX_SEGMENT_SIZE,
+        }
+    }
+}
+
+/// Stats returned by [`BinaryIndex::compact`] describing the space reclaimed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompactionStats {
+    pub reclaimed_bytes: u64,
+    pub live_vectors: usize,
+    pub dead_vectors: usize,
+}
+
+/// Report returned by [`BinaryIndex::verify`]: which records failed to read
+/// back (wrong checksum, failed decompression/decryption, or corrupt
+/// metadata) and whether replaying the on-disk footer and index-log
+/// reproduces the entries currently held in memory.
+#[derive(Debug, Clone, Default)]
+pub struct VerifyReport {
+    pub checked: usize,
+    pub header_ok: bool,
+    pub corrupt: Vec<(Uuid, String)>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.header_ok && self.corrupt.is_empty()
+    }
+}
 
 #[derive(Debug)]
 pub struct BinaryIndex {
@@ -16,50 +272,315 @@ pub struct BinaryIndex {
     dimension: usize,
     vector_count: usize,
     index_entries: HashMap<Uuid, IndexEntry>,
+    config: BinaryIndexConfig,
+    dead_bytes: u64,
+    live_bytes: u64,
+    encryption: EncryptionType,
+    salt: [u8; SALT_SIZE],
+    cipher: Option<Cipher>,
+    /// Offset of the footer (serialized index entries) written by the last
+    /// `flush()`/checkpoint; 0 if none has been written yet.
+    footer_offset: u64,
+    /// Checksum of that footer's bytes.
+    footer_checksum: u64,
+    /// Segment currently being appended to by `add_vector`.
+    current_segment: u32,
+    /// Bytes written so far to `current_segment`, used to decide when to
+    /// roll over to the next one.
+    current_segment_size: u64,
+    /// Set just before `compact()` replaces `self` wholesale, so the
+    /// outgoing value's `Drop` doesn't flush its now-superseded in-memory
+    /// state over the files the replacement just took ownership of.
+    suppress_flush_on_drop: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct IndexEntry {
+    /// Which segment file (`vectors.NNN.kwi`) this record lives in.
+    segment: u32,
     offset: u64,
     dimension: u32,
     metadata_size: u32,
+    dead: bool,
+    codec: CompressionAlgorithm,
+    /// Size of the uncompressed data+metadata payload (4-byte length +
+    /// bincode-serialized data + 4-byte length + metadata_size).
+    uncompressed_size: u32,
+    /// Size of the payload as actually stored on disk (equal to
+    /// `uncompressed_size` when `codec` is `None` and the index is not
+    /// encrypted).
+    compressed_size: u32,
+    /// xxh3_64 of the record's on-disk bytes (post-compression and
+    /// post-encryption), checked by `get_vector` and `verify`.
+    checksum: u64,
+}
+
+impl IndexEntry {
+    /// Size in bytes of the record body this entry points at on disk: the
+    /// codec tag, the two length prefixes, and the (possibly compressed)
+    /// payload.
+    fn record_size(&self) -> u64 {
+        1 + 4 + 4 + self.compressed_size as u64
+    }
+}
+
+/// Path of segment file number `segment` for the index whose header lives
+/// at `index_path`, e.g. `vectors.kwi` -> `vectors.000.kwi`. A free function
+/// (rather than a `&self` method) so `compact()` can compute both the old
+/// and new segment paths while moving between two different index paths.
+fn segment_path(index_path: &Path, segment: u32) -> std::path::PathBuf {
+    let stem = index_path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let extension = index_path.extension().unwrap_or_default().to_string_lossy().into_owned();
+
+    let file_name = if extension.is_empty() {
+        format!("{}.{:03}", stem, segment)
+    } else {
+        format!("{}.{:03}.{}", stem, segment, extension)
+    };
+
+    index_path.with_file_name(file_name)
+}
+
+/// Path of the append-only index-log sidecar for the index whose header
+/// lives at `index_path`, e.g. `vectors.kwi` -> `vectors.kwi.log`. Appends
+/// the whole original file name (rather than swapping the extension) for
+/// the same reason as `segment_path`: so `compact()`'s temporary index
+/// (`vectors.tmp`) never computes a log path that collides with the
+/// original's.
+pub(crate) fn log_path(index_path: &Path) -> std::path::PathBuf {
+    let file_name = format!("{}.log", index_path.file_name().unwrap_or_default().to_string_lossy());
+    index_path.with_file_name(file_name)
+}
+
+/// All segment files currently on disk for the index whose header lives at
+/// `index_path`, discovered by scanning its directory for names matching
+/// `segment_path`'s convention rather than trusting a live `BinaryIndex`'s
+/// `current_segment` counter — callers like the snapshot/restore subsystem
+/// in `collection_manager` only have a directory, not an open index, and
+/// still need to know exactly which segment files exist. Sorted by segment
+/// number.
+pub(crate) fn existing_segment_paths(index_path: &Path) -> Vec<std::path::PathBuf> {
+    let stem = index_path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let extension = index_path.extension().unwrap_or_default().to_string_lossy().into_owned();
+    let dir = index_path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut segments: Vec<(u32, std::path::PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            let segment_part = if extension.is_empty() {
+                name.strip_prefix(&format!("{}.", stem))?
+            } else {
+                name.strip_prefix(&format!("{}.", stem))?
+                    .strip_suffix(&format!(".{}", extension))?
+            };
+            if segment_part.len() == 3 && segment_part.bytes().all(|b| b.is_ascii_digit()) {
+                Some((segment_part.parse().ok()?, path))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    segments.sort_by_key(|(segment, _)| *segment);
+    segments.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Writes one index entry (id plus fields) in the fixed `ENTRY_RECORD_SIZE`
+/// layout shared by the footer and the index-log, so both can be read back
+/// with `read_entry_record`.
+fn write_entry_record<W: Write>(w: &mut W, id: &Uuid, entry: &IndexEntry) -> Result<()> {
+    w.write_all(id.as_bytes())?;
+    w.write_u32::<LittleEndian>(entry.segment)?;
+    w.write_u64::<LittleEndian>(entry.offset)?;
+    w.write_u32::<LittleEndian>(entry.dimension)?;
+    w.write_u32::<LittleEndian>(entry.metadata_size)?;
+    w.write_u8(entry.dead as u8)?;
+    w.write_u8(entry.codec.tag())?;
+    w.write_u32::<LittleEndian>(entry.uncompressed_size)?;
+    w.write_u32::<LittleEndian>(entry.compressed_size)?;
+    w.write_u64::<LittleEndian>(entry.checksum)?;
+    Ok(())
+}
+
+/// Inverse of `write_entry_record`.
+fn read_entry_record<R: Read>(r: &mut R) -> Result<(Uuid, IndexEntry)> {
+    let mut id_bytes = [0u8; 16];
+    r.read_exact(&mut id_bytes)?;
+    let id = Uuid::from_bytes(id_bytes);
+
+    let entry = IndexEntry {
+        segment: r.read_u32::<LittleEndian>()?,
+        offset: r.read_u64::<LittleEndian>()?,
+        dimension: r.read_u32::<LittleEndian>()?,
+        metadata_size: r.read_u32::<LittleEndian>()?,
+        dead: r.read_u8()? != 0,
+        codec: CompressionAlgorithm::from_tag(r.read_u8()?)?,
+        uncompressed_size: r.read_u32::<LittleEndian>()?,
+        compressed_size: r.read_u32::<LittleEndian>()?,
+        checksum: r.read_u64::<LittleEndian>()?,
+    };
+
+    Ok((id, entry))
+}
+
+/// Reconstructs the logical index-entries map purely from what is on disk
+/// right now: the footer written at the last checkpoint (if any), folded
+/// with whatever the append-only index-log has recorded since (replayed in
+/// append order, so a later write for the same id wins). This is what a
+/// crash right after the last acknowledged `add_vector`/`delete_vector`
+/// would recover, and what `load_index`/`verify` both build on.
+fn read_persisted_entries(file_path: &Path) -> Result<HashMap<Uuid, IndexEntry>> {
+    let mut entries = HashMap::new();
+
+    let mut file = File::open(file_path)
+        .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open index file: {}", e)))?;
+
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)?;
+    if magic != *KWI_MAGIC {
+        return Err(crate::VectorDBError::PersistenceError("Invalid KWI file format".to_string()));
+    }
+    let version = file.read_u32::<LittleEndian>()?;
+    if version != KWI_VERSION {
+        return Err(crate::VectorDBError::PersistenceError(format!("Unsupported KWI version: {}", version)));
+    }
+    let _dimension = file.read_u32::<LittleEndian>()?;
+    let _vector_count = file.read_u64::<LittleEndian>()?;
+    let footer_entry_count = file.read_u64::<LittleEndian>()?;
+    let footer_offset = file.read_u64::<LittleEndian>()?;
+    let _footer_checksum = file.read_u64::<LittleEndian>()?;
+    let _encryption = file.read_u8()?;
+    let mut salt = [0u8; SALT_SIZE];
+    file.read_exact(&mut salt)?;
+
+    if footer_offset > 0 {
+        file.seek(SeekFrom::Start(footer_offset))?;
+        let mut reader = BufReader::new(&file);
+        for _ in 0..footer_entry_count {
+            let (id, entry) = read_entry_record(&mut reader)?;
+            entries.insert(id, entry);
+        }
+    }
+
+    let log_file_path = log_path(file_path);
+    if log_file_path.exists() {
+        let log_file = File::open(&log_file_path)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open index log: {}", e)))?;
+        let log_len = log_file.metadata()?.len();
+        let mut reader = BufReader::new(log_file);
+        let mut read = 0u64;
+        while read < log_len {
+            let (id, entry) = read_entry_record(&mut reader)?;
+            read += ENTRY_RECORD_SIZE;
+            entries.insert(id, entry);
+        }
+    }
+
+    Ok(entries)
 }
 
 impl BinaryIndex {
     pub fn new<P: AsRef<Path>>(index_path: P, dimension: usize) -> Result<Self> {
+        Self::new_with_config(index_path, dimension, BinaryIndexConfig::default())
+    }
+
+    pub fn new_with_config<P: AsRef<Path>>(
+        index_path: P,
+        dimension: usize,
+        config: BinaryIndexConfig,
+    ) -> Result<Self> {
+        Self::new_with_opener(index_path, dimension, config, BinaryIndexOpener::none())
+    }
+
+    /// Like [`new_with_config`](Self::new_with_config), but takes a
+    /// [`BinaryIndexOpener`] carrying the passphrase (if any) needed to
+    /// encrypt new records or decrypt existing ones. Files created with
+    /// [`BinaryIndexOpener::none`] are plaintext and remain readable by
+    /// older callers that never pass an opener at all.
+    pub fn new_with_opener<P: AsRef<Path>>(
+        index_path: P,
+        dimension: usize,
+        config: BinaryIndexConfig,
+        opener: BinaryIndexOpener,
+    ) -> Result<Self> {
         let file_path = index_path.as_ref().to_path_buf();
-        
+
         let mut index = Self {
             file_path,
             dimension,
             vector_count: 0,
             index_entries: HashMap::new(),
+            config,
+            dead_bytes: 0,
+            live_bytes: 0,
+            encryption: EncryptionType::None,
+            salt: [0u8; SALT_SIZE],
+            cipher: None,
+            footer_offset: 0,
+            footer_checksum: 0,
+            current_segment: 0,
+            current_segment_size: 0,
+            suppress_flush_on_drop: false,
         };
 
         if index.file_path.exists() {
-            index.load_index()?;
+            index.load_index(&opener)?;
         } else {
-            index.create_new_index()?;
+            index.create_new_index(&opener)?;
         }
 
         Ok(index)
     }
 
-    fn create_new_index(&mut self) -> Result<()> {
-        let mut file = File::create(&self.file_path)
+    fn create_new_index(&mut self, opener: &BinaryIndexOpener) -> Result<()> {
+        if let Some((encryption, passphrase)) = &opener.passphrase {
+            use rand::RngCore;
+            let mut salt = [0u8; SALT_SIZE];
+            rand::thread_rng().fill_bytes(&mut salt);
+
+            let key = BinaryIndexOpener::derive_key(passphrase, &salt)?;
+            self.cipher = Some(Cipher::new(*encryption, &key)?);
+            self.encryption = *encryption;
+            self.salt = salt;
+        }
+
+        // A stale log left over from a previous file at this path (e.g. one
+        // deleted out from under us) would otherwise get replayed into a
+        // brand-new, otherwise-empty index.
+        let _ = std::fs::remove_file(self.log_path());
+
+        self.write_new_header()
+    }
+
+    /// Writes a fresh header (magic, version, empty counts, no footer yet,
+    /// and the encryption fields already set on `self`) to a brand-new file
+    /// at `self.file_path`.
+    fn write_new_header(&self) -> Result<()> {
+        let file = File::create(&self.file_path)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to create index file: {}", e)))?;
+        let mut writer = BufWriter::new(file);
 
-        // Write header
-        file.write_all(KWI_MAGIC)?;
-        file.write_u32::<LittleEndian>(KWI_VERSION)?;
-        file.write_u32::<LittleEndian>(self.dimension as u32)?;
-        file.write_u64::<LittleEndian>(0)?; // vector count
-        file.write_u64::<LittleEndian>(0)?; // reserved
+        writer.write_all(KWI_MAGIC)?;
+        writer.write_u32::<LittleEndian>(KWI_VERSION)?;
+        writer.write_u32::<LittleEndian>(self.dimension as u32)?;
+        writer.write_u64::<LittleEndian>(0)?; // vector count
+        writer.write_u64::<LittleEndian>(0)?; // footer entry count
+        writer.write_u64::<LittleEndian>(0)?; // footer offset (no footer yet)
+        writer.write_u64::<LittleEndian>(0)?; // footer checksum
+        writer.write_u8(self.encryption.tag())?;
+        writer.write_all(&self.salt)?;
+        writer.flush()?;
 
         Ok(())
     }
 
-    fn load_index(&mut self) -> Result<()> {
+    fn load_index(&mut self, opener: &BinaryIndexOpener) -> Result<()> {
         let mut file = File::open(&self.file_path)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open index file: {}", e)))?;
 
@@ -76,43 +597,137 @@ impl BinaryIndex {
         }
 
         self.dimension = file.read_u32::<LittleEndian>()? as usize;
-        self.vector_count = file.read_u64::<LittleEndian>()? as usize;
-        let _reserved = file.read_u64::<LittleEndian>()?;
-
-        // Read index entries
-        for _ in 0..self.vector_count {
-            let id_bytes = {
-                let mut bytes = [0u8; 16];
-                file.read_exact(&mut bytes)?;
-                bytes
-            };
-            let id = Uuid::from_bytes(id_bytes);
+        let _vector_count_hint = file.read_u64::<LittleEndian>()?;
+        let _footer_entry_count = file.read_u64::<LittleEndian>()?;
+        self.footer_offset = file.read_u64::<LittleEndian>()?;
+        self.footer_checksum = file.read_u64::<LittleEndian>()?;
 
-            let entry = IndexEntry {
-                offset: file.read_u64::<LittleEndian>()?,
-                dimension: file.read_u32::<LittleEndian>()?,
-                metadata_size: file.read_u32::<LittleEndian>()?,
-            };
+        self.encryption = EncryptionType::from_tag(file.read_u8()?)?;
+        file.read_exact(&mut self.salt)?;
+
+        if self.encryption != EncryptionType::None {
+            let passphrase = opener.passphrase.as_ref().map(|(_, p)| p.as_str()).ok_or_else(|| {
+                crate::VectorDBError::IntegrityError(
+                    "Index file is encrypted but no passphrase was provided".to_string(),
+                )
+            })?;
+            let key = BinaryIndexOpener::derive_key(passphrase, &self.salt)?;
+            self.cipher = Some(Cipher::new(self.encryption, &key)?);
+        }
 
-            self.index_entries.insert(id, entry);
+        drop(file);
+
+        // Reconstruct live state from the last checkpointed footer folded
+        // with whatever the index-log has recorded since, so a crash
+        // between checkpoints never loses an acknowledged
+        // `add_vector`/`delete_vector` (dead entries are kept so their
+        // space is accounted for until the next compaction).
+        for (id, entry) in read_persisted_entries(&self.file_path)? {
+            self.apply_entry(id, entry);
         }
 
+        self.current_segment_size = std::fs::metadata(self.segment_path(self.current_segment))
+            .map(|m| m.len())
+            .unwrap_or(0);
+
         Ok(())
     }
 
-    pub fn add_vector(&mut self, vector: &Vector) -> Result<()> {
+    /// Upserts `entry` under `id`, adjusting `live_bytes`/`dead_bytes`/
+    /// `vector_count`/`current_segment` bookkeeping relative to whatever
+    /// entry (if any) previously occupied that id. Shared by `load_index`
+    /// (applying the persisted footer+log), log replay, and `add_vector`/
+    /// `delete_vector` themselves, so the accounting can never drift
+    /// between the write path and the recovery path.
+    fn apply_entry(&mut self, id: Uuid, entry: IndexEntry) {
+        if let Some(old) = self.index_entries.get(&id) {
+            if old.dead {
+                self.dead_bytes = self.dead_bytes.saturating_sub(old.record_size());
+            } else {
+                self.live_bytes = self.live_bytes.saturating_sub(old.record_size());
+                self.vector_count = self.vector_count.saturating_sub(1);
+            }
+        }
+
+        if entry.dead {
+            self.dead_bytes += entry.record_size();
+        } else {
+            self.live_bytes += entry.record_size();
+            self.vector_count += 1;
+        }
+
+        self.current_segment = self.current_segment.max(entry.segment);
+        self.index_entries.insert(id, entry);
+    }
+
+    fn log_path(&self) -> std::path::PathBuf {
+        log_path(&self.file_path)
+    }
+
+    /// Appends one entry to the index-log sidecar: the durability step that
+    /// makes `add_vector`/`delete_vector` crash-safe between checkpoints
+    /// without paying to rewrite the whole footer every time.
+    fn append_log_entry(&self, id: &Uuid, entry: &IndexEntry) -> Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.log_path())
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open index log: {}", e)))?;
+
+        let mut writer = BufWriter::new(file);
+        write_entry_record(&mut writer, id, entry)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Folds the in-memory entries into a fresh footer appended to the main
+    /// file, points the header at it, and truncates the index-log (now
+    /// redundant since every entry it held is captured in the footer). This
+    /// is the only place the full entries table gets rewritten, so bulk
+    /// insertion no longer pays an O(n) header rewrite on every single
+    /// `add_vector`. Called automatically on `Drop`; call it directly to
+    /// force durability at a specific point instead of whenever the process
+    /// happens to exit.
+    pub fn flush(&mut self) -> Result<()> {
+        let entries_bytes = self.serialize_entries();
+        let footer_checksum = checksum(&entries_bytes);
+
         let mut file = OpenOptions::new()
             .read(true)
             .write(true)
             .open(&self.file_path)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open index file: {}", e)))?;
 
-        // Seek to end of file
-        let file_size = file.metadata()?.len();
-        file.seek(SeekFrom::End(0))?;
+        let footer_offset = file.seek(SeekFrom::End(0))?;
+        {
+            let mut writer = BufWriter::new(&file);
+            writer.write_all(&entries_bytes)?;
+            writer.flush()?;
+        }
+
+        file.seek(SeekFrom::Start(12))?; // Skip magic, version, dimension
+        file.write_u64::<LittleEndian>(self.vector_count as u64)?;
+        file.write_u64::<LittleEndian>(self.index_entries.len() as u64)?; // footer entry count
+        file.write_u64::<LittleEndian>(footer_offset)?;
+        file.write_u64::<LittleEndian>(footer_checksum)?;
+        file.flush()?;
+
+        self.footer_offset = footer_offset;
+        self.footer_checksum = footer_checksum;
+
+        // Everything the log held is now durable in the footer.
+        File::create(self.log_path())
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to truncate index log: {}", e)))?;
 
-        let offset = file.stream_position()?;
+        Ok(())
+    }
+
+    fn segment_path(&self, segment: u32) -> std::path::PathBuf {
+        segment_path(&self.file_path, segment)
+    }
 
+    pub fn add_vector(&mut self, vector: &Vector) -> Result<()> {
         // Write vector data
         let data_bytes = bincode::serialize(&vector.data)
             .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize vector data: {}", e)))?;
@@ -124,52 +739,148 @@ impl BinaryIndex {
             Vec::new()
         };
 
-        // Write vector record
-        file.write_all(&data_bytes)?;
-        file.write_u32::<LittleEndian>(metadata_bytes.len() as u32)?;
-        file.write_all(&metadata_bytes)?;
+        // The payload is a length-prefixed data blob followed by a
+        // length-prefixed metadata blob; this whole thing is what gets
+        // compressed as one unit. `data_bytes` is the bincode framing of an
+        // `Array1<f32>`, not a bare `dimension * 4` byte run, so it needs
+        // its own length prefix to be read back exactly (bincode can't
+        // self-delimit from a slice that runs past its actual content).
+        let mut payload = Vec::with_capacity(4 + data_bytes.len() + 4 + metadata_bytes.len());
+        payload.extend_from_slice(&(data_bytes.len() as u32).to_le_bytes());
+        payload.extend_from_slice(&data_bytes);
+        payload.extend_from_slice(&(metadata_bytes.len() as u32).to_le_bytes());
+        payload.extend_from_slice(&metadata_bytes);
+
+        let uncompressed_size = payload.len() as u32;
+        let compressed = self.config.compression.compress(&payload)?;
+        let (codec, stored_bytes) = if compressed.len() < payload.len() {
+            (self.config.compression, compressed)
+        } else {
+            (CompressionAlgorithm::None, payload)
+        };
+
+        // If encryption is configured, the on-disk payload becomes a fresh
+        // per-record nonce followed by the AEAD-sealed (codec-compressed)
+        // bytes; otherwise it's exactly `stored_bytes`.
+        let on_disk_bytes = if let Some(cipher) = &self.cipher {
+            use rand::RngCore;
+            let mut nonce = [0u8; NONCE_SIZE];
+            rand::thread_rng().fill_bytes(&mut nonce);
+            let ciphertext = cipher.seal(&nonce, &stored_bytes)?;
+
+            let mut on_disk = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+            on_disk.extend_from_slice(&nonce);
+            on_disk.extend_from_slice(&ciphertext);
+            on_disk
+        } else {
+            stored_bytes
+        };
+
+        let record_checksum = checksum(&on_disk_bytes);
+        let record_size = 1 + 4 + 4 + on_disk_bytes.len() as u64;
+
+        // Roll over to a new segment if this record would push the current
+        // one past the configured cap (but never roll an empty segment).
+        if self.current_segment_size > 0 && self.current_segment_size + record_size > self.config.max_segment_size {
+            self.current_segment += 1;
+            self.current_segment_size = 0;
+        }
+
+        let mut segment_file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .open(self.segment_path(self.current_segment))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open segment file: {}", e)))?;
+
+        segment_file.seek(SeekFrom::End(0))?;
+        let offset = segment_file.stream_position()?;
+
+        {
+            let mut writer = BufWriter::new(&segment_file);
+            writer.write_u8(codec.tag())?;
+            writer.write_u32::<LittleEndian>(uncompressed_size)?;
+            writer.write_u32::<LittleEndian>(on_disk_bytes.len() as u32)?;
+            writer.write_all(&on_disk_bytes)?;
+            writer.flush()?;
+        }
+
+        self.current_segment_size += record_size;
 
-        // Update index entry
         let entry = IndexEntry {
+            segment: self.current_segment,
             offset,
             dimension: vector.dimension() as u32,
             metadata_size: metadata_bytes.len() as u32,
+            dead: false,
+            codec,
+            uncompressed_size,
+            compressed_size: on_disk_bytes.len() as u32,
+            checksum: record_checksum,
         };
 
-        self.index_entries.insert(vector.id, entry);
-        self.vector_count += 1;
+        self.apply_entry(vector.id, entry.clone());
+        self.append_log_entry(&vector.id, &entry)?;
 
-        // Update header
-        self.update_header(&mut file)?;
+        self.maybe_auto_compact()?;
 
         Ok(())
     }
 
     pub fn get_vector(&self, id: &Uuid) -> Result<Option<Vector>> {
         let entry = match self.index_entries.get(id) {
-            Some(entry) => entry,
-            None => return Ok(None),
+            Some(entry) if !entry.dead => entry,
+            _ => return Ok(None),
         };
 
-        let mut file = File::open(&self.file_path)
-            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open index file: {}", e)))?;
+        let mut file = File::open(self.segment_path(entry.segment))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open segment file: {}", e)))?;
 
         file.seek(SeekFrom::Start(entry.offset))?;
+        let mut reader = BufReader::new(file);
+
+        let _codec_tag = reader.read_u8()?;
+        let _uncompressed_size = reader.read_u32::<LittleEndian>()?;
+        let on_disk_size = reader.read_u32::<LittleEndian>()? as usize;
+
+        let mut on_disk_bytes = vec![0u8; on_disk_size];
+        reader.read_exact(&mut on_disk_bytes)?;
+
+        if checksum(&on_disk_bytes) != entry.checksum {
+            return Err(crate::VectorDBError::IntegrityError(format!(
+                "Checksum mismatch for record {}: record is corrupt",
+                id
+            )));
+        }
 
-        // Read vector data
-        let data_size = entry.dimension as usize * 4; // f32 = 4 bytes
-        let mut data_bytes = vec![0u8; data_size];
-        file.read_exact(&mut data_bytes)?;
+        let stored_bytes = if let Some(cipher) = &self.cipher {
+            if on_disk_bytes.len() < NONCE_SIZE {
+                return Err(crate::VectorDBError::IntegrityError(
+                    "Encrypted record is too short to contain a nonce".to_string(),
+                ));
+            }
+            let (nonce_bytes, ciphertext) = on_disk_bytes.split_at(NONCE_SIZE);
+            let nonce: [u8; NONCE_SIZE] = nonce_bytes.try_into().unwrap();
+            cipher.open(&nonce, ciphertext)?
+        } else {
+            on_disk_bytes
+        };
+
+        let payload = entry.codec.decompress(&stored_bytes, entry.uncompressed_size as usize)?;
 
-        let data: Array1<f32> = bincode::deserialize(&data_bytes)
+        // Payload is a length-prefixed data blob followed by a
+        // length-prefixed metadata blob.
+        let data_size = u32::from_le_bytes(payload[0..4].try_into().unwrap()) as usize;
+        let data_bytes = &payload[4..4 + data_size];
+
+        let data: Array1<f32> = bincode::deserialize(data_bytes)
             .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize vector data: {}", e)))?;
 
-        // Read metadata
-        let metadata_size = file.read_u32::<LittleEndian>()? as usize;
+        let metadata_offset = 4 + data_size;
+        let metadata_size = u32::from_le_bytes(payload[metadata_offset..metadata_offset + 4].try_into().unwrap()) as usize;
         let metadata = if metadata_size > 0 {
-            let mut metadata_bytes = vec![0u8; metadata_size];
-            file.read_exact(&mut metadata_bytes)?;
-            Some(serde_json::from_slice(&metadata_bytes)
+            let metadata_bytes = &payload[metadata_offset + 4..metadata_offset + 4 + metadata_size];
+            Some(serde_json::from_slice(metadata_bytes)
                 .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize metadata: {}", e)))?)
         } else {
             None
@@ -182,10 +893,16 @@ impl BinaryIndex {
         }))
     }
 
+    /// Reads back every live vector. Records are spread across independent
+    /// segment files, so nothing here depends on a particular segment
+    /// ordering or on a single shared file handle.
     pub fn get_all_vectors(&self) -> Result<Vec<Vector>> {
         let mut vectors = Vec::new();
-        
-        for (id, _entry) in &self.index_entries {
+
+        for (id, entry) in &self.index_entries {
+            if entry.dead {
+                continue;
+            }
             if let Some(vector) = self.get_vector(id)? {
                 vectors.push(vector);
             }
@@ -194,23 +911,27 @@ impl BinaryIndex {
         Ok(vectors)
     }
 
+    /// Tombstones the record for `id` instead of rewriting the file. The
+    /// space it occupies is reclaimed the next time [`compact`](Self::compact)
+    /// runs (which `add_vector`/`delete_vector` may trigger automatically,
+    /// see [`BinaryIndexConfig::auto_compact_threshold`]).
     pub fn delete_vector(&mut self, id: &Uuid) -> Result<()> {
-        if self.index_entries.remove(id).is_some() {
-            self.vector_count -= 1;
-            
-            // Update header
-            let mut file = OpenOptions::new()
-                .read(true)
-                .write(true)
-                .open(&self.file_path)
-                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open index file: {}", e)))?;
+        let entry = match self.index_entries.get(id) {
+            Some(entry) if !entry.dead => entry.clone(),
+            _ => return Ok(()),
+        };
 
-            self.update_header(&mut file)?;
-        }
+        let mut tombstoned = entry;
+        tombstoned.dead = true;
+        self.apply_entry(*id, tombstoned.clone());
+        self.append_log_entry(id, &tombstoned)?;
+
+        self.maybe_auto_compact()?;
 
         Ok(())
     }
 
+    /// Number of live (non-tombstoned) vectors.
     pub fn count_vectors(&self) -> usize {
         self.vector_count
     }
@@ -219,40 +940,262 @@ impl BinaryIndex {
         self.dimension
     }
 
-    fn update_header(&self, file: &mut File) -> Result<()> {
-        file.seek(SeekFrom::Start(16))?; // Skip magic, version, dimension
-        file.write_u64::<LittleEndian>(self.vector_count as u64)?;
+    /// Bytes occupied by tombstoned records that `compact()` would reclaim.
+    pub fn dead_bytes(&self) -> u64 {
+        self.dead_bytes
+    }
+
+    /// Bytes occupied by live record bodies (excludes header/index region).
+    pub fn live_bytes(&self) -> u64 {
+        self.live_bytes
+    }
+
+    /// Total logical (uncompressed) vs. physical (on-disk, post-compression)
+    /// bytes across all live records, so callers can report space savings.
+    pub fn compression_stats(&self) -> (u64, u64) {
+        self.index_entries
+            .values()
+            .filter(|e| !e.dead)
+            .fold((0u64, 0u64), |(logical, physical), entry| {
+                (
+                    logical + entry.uncompressed_size as u64,
+                    physical + entry.compressed_size as u64,
+                )
+            })
+    }
+
+    /// Serializes the index entries in a canonical (UUID-sorted) order so
+    /// that the footer's checksum depends only on the entries' content, not
+    /// on `HashMap` iteration order (which can differ across reloads of the
+    /// same data).
+    fn serialize_entries(&self) -> Vec<u8> {
+        let mut entries: Vec<(&Uuid, &IndexEntry)> = self.index_entries.iter().collect();
+        entries.sort_by_key(|(id, _)| **id);
+
+        let mut buf = Vec::with_capacity(entries.len() * ENTRY_RECORD_SIZE as usize);
+        for (id, entry) in entries {
+            write_entry_record(&mut buf, id, entry).expect("writing to a Vec<u8> cannot fail");
+        }
+        buf
+    }
+
+    /// Streams every live, readable record out as JSONL
+    /// (`{id, dimension, data, metadata}` per line), independent of
+    /// `KWI_VERSION` or bincode layout. Records that fail to read back
+    /// (e.g. a corrupt tail) are silently skipped rather than aborting the
+    /// whole dump, so this also doubles as a best-effort recovery tool.
+    /// Returns the number of records written.
+    pub fn dump<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let mut ids: Vec<&Uuid> = self.index_entries.keys().collect();
+        ids.sort();
+
+        let mut written = 0;
+        for id in ids {
+            if self.index_entries[id].dead {
+                continue;
+            }
+            let vector = match self.get_vector(id) {
+                Ok(Some(vector)) => vector,
+                _ => continue,
+            };
+
+            let record = serde_json::json!({
+                "id": vector.id,
+                "dimension": vector.dimension(),
+                "data": vector.data.to_vec(),
+                "metadata": vector.metadata,
+            });
+            serde_json::to_writer(&mut writer, &record)
+                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to write dump record: {}", e)))?;
+            writer.write_all(b"\n")?;
+            written += 1;
+        }
+
+        Ok(written)
+    }
+
+    /// Rebuilds a fresh `.kwi` file at `path` from a JSONL stream produced
+    /// by [`dump`](Self::dump). Lines that don't parse as a well-formed
+    /// record (or whose `data` length doesn't match `dimension`) are
+    /// skipped rather than aborting the restore.
+    pub fn restore<R: std::io::BufRead, P: AsRef<Path>>(reader: R, path: P, dimension: usize) -> Result<Self> {
+        let mut index = Self::new(path, dimension)?;
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+
+            let id = match record.get("id").and_then(|v| v.as_str()).and_then(|s| Uuid::parse_str(s).ok()) {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let data: Vec<f32> = match record.get("data").and_then(|v| v.as_array()) {
+                Some(values) => values.iter().filter_map(|v| v.as_f64()).map(|f| f as f32).collect(),
+                None => continue,
+            };
+            if data.len() != dimension {
+                continue;
+            }
+
+            let mut vector = Vector::with_id(id, Array1::from_vec(data));
+            vector.metadata = record.get("metadata").cloned().filter(|v| !v.is_null());
+
+            index.add_vector(&vector)?;
+        }
+
+        index.flush()?;
+
+        Ok(index)
+    }
+
+    /// Walks every live record, recomputing checksums and re-parsing it the
+    /// way `get_vector` would, and reports any that fail. Also checks that
+    /// replaying what's actually on disk (the last checkpointed footer
+    /// folded with the index-log) reproduces the in-memory entries exactly.
+    pub fn verify(&self) -> Result<VerifyReport> {
+        let header_ok = read_persisted_entries(&self.file_path)
+            .map(|disk_entries| disk_entries == self.index_entries)
+            .unwrap_or(false);
+
+        let mut report = VerifyReport {
+            checked: 0,
+            header_ok,
+            corrupt: Vec::new(),
+        };
 
-        // Write updated index entries
-        file.seek(SeekFrom::Start(32))?; // Skip header
-        
         for (id, entry) in &self.index_entries {
-            file.write_all(id.as_bytes())?;
-            file.write_u64::<LittleEndian>(entry.offset)?;
-            file.write_u32::<LittleEndian>(entry.dimension)?;
-            file.write_u32::<LittleEndian>(entry.metadata_size)?;
+            if entry.dead {
+                continue;
+            }
+            report.checked += 1;
+            if let Err(e) = self.get_vector(id) {
+                report.corrupt.push((*id, e.to_string()));
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn maybe_auto_compact(&mut self) -> Result<()> {
+        let Some(threshold) = self.config.auto_compact_threshold else {
+            return Ok(());
+        };
+
+        // Measured against live+dead record bytes rather than the main
+        // file's size: since records live in segment files and the footer
+        // is only rewritten at a checkpoint, `self.file_path` alone is no
+        // longer a meaningful proxy for how much data the store actually
+        // holds.
+        let total_bytes = self.live_bytes + self.dead_bytes;
+        if total_bytes > 0 && self.dead_bytes as f64 / total_bytes as f64 > threshold {
+            self.compact()?;
         }
 
         Ok(())
     }
 
-    pub fn optimize(&mut self) -> Result<()> {
-        // Create a new optimized index file
+    /// Rewrites the index, keeping only live records, and reports how much
+    /// space was reclaimed. Supersedes the old `optimize()` (kept as an
+    /// alias for backward compatibility).
+    pub fn compact(&mut self) -> Result<CompactionStats> {
+        let dead_vectors = self.index_entries.values().filter(|e| e.dead).count();
+        let live_vectors = self.index_entries.len() - dead_vectors;
+        let reclaimed_bytes = self.dead_bytes;
+
+        // Built directly (rather than via `new_with_opener`) so that an
+        // encrypted index can be compacted using the key material already
+        // derived in memory, without needing the passphrase again.
         let temp_path = self.file_path.with_extension("tmp");
-        let mut optimized_index = BinaryIndex::new(&temp_path, self.dimension)?;
+        let mut compacted_index = Self {
+            file_path: temp_path.clone(),
+            dimension: self.dimension,
+            vector_count: 0,
+            index_entries: HashMap::new(),
+            config: self.config,
+            dead_bytes: 0,
+            live_bytes: 0,
+            encryption: self.encryption,
+            salt: self.salt,
+            cipher: self.cipher.clone(),
+            footer_offset: 0,
+            footer_checksum: 0,
+            current_segment: 0,
+            current_segment_size: 0,
+            suppress_flush_on_drop: false,
+        };
+        compacted_index.write_new_header()?;
 
-        // Re-add all vectors in order
         let vectors = self.get_all_vectors()?;
         for vector in vectors {
-            optimized_index.add_vector(&vector)?;
+            compacted_index.add_vector(&vector)?;
+        }
+
+        // Fold the compacted index's own entries into a real footer (and
+        // truncate its log) before it takes over `self.file_path`, so the
+        // result of a compaction is never itself relying on a long log
+        // replay.
+        compacted_index.flush()?;
+
+        // Drop this index's old segment files (everything live has already
+        // been copied into `compacted_index`'s, under temp-path naming).
+        for old_segment in 0..=self.current_segment {
+            let _ = std::fs::remove_file(self.segment_path(old_segment));
         }
 
-        // Replace old file with optimized one
+        // The original's index-log may still hold entries for records that
+        // no longer exist post-compaction; both it and the (already empty,
+        // just-flushed) temp log are superseded by the footer just written.
+        let _ = std::fs::remove_file(log_path(&temp_path));
+        let _ = std::fs::remove_file(self.log_path());
+
         std::fs::rename(&temp_path, &self.file_path)?;
-        
-        // Update self with optimized index
-        *self = optimized_index;
 
+        // Move the compacted segment files over to the final naming (they
+        // were written under `temp_path`'s stem).
+        for new_segment in 0..=compacted_index.current_segment {
+            let from = segment_path(&temp_path, new_segment);
+            if from.exists() {
+                std::fs::rename(from, segment_path(&self.file_path, new_segment))?;
+            }
+        }
+
+        compacted_index.file_path = self.file_path.clone();
+
+        // `self` is about to be overwritten wholesale; suppress its Drop so
+        // it doesn't flush now-stale state over the files that just took
+        // its place.
+        self.suppress_flush_on_drop = true;
+        *self = compacted_index;
+
+        Ok(CompactionStats {
+            reclaimed_bytes,
+            live_vectors,
+            dead_vectors,
+        })
+    }
+
+    /// Deprecated alias for [`compact`](Self::compact).
+    pub fn optimize(&mut self) -> Result<()> {
+        self.compact()?;
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+impl Drop for BinaryIndex {
+    /// Best-effort checkpoint so an index that's never had `flush()` called
+    /// explicitly still ends up with its footer up to date rather than
+    /// relying solely on the index-log being replayed next time.
+    fn drop(&mut self) {
+        if !self.suppress_flush_on_drop {
+            let _ = self.flush();
+        }
+    }
+}
@@ -1,11 +1,33 @@
 use crate::{vector::Vector, Result};
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use super::sqlite_storage::SQLiteStorage;
-use super::binary_index::BinaryIndex;
+use super::embedder::Embedder;
+use super::backend::{BackendKind, CollectionBackend, InMemoryBackend, JsonBackend, SqliteBinaryBackend};
+
+/// Marker file recording which [`BackendKind`] a collection directory (or
+/// snapshot directory) was written with, so it can be re-opened as the right
+/// concrete type without guessing from whichever data files happen to exist.
+const BACKEND_MARKER_FILE: &str = ".backend";
+
+/// Prefix of a transaction's write-ahead marker file, named
+/// `<prefix><epoch>` in the collection directory. Written just before
+/// [`CollectionManager::commit`] applies a batch and removed right after, so
+/// a marker found at `load_collection` time means the prior process crashed
+/// mid-apply and the batch needs replaying.
+const WAL_MARKER_PREFIX: &str = ".wal-";
+
+/// The staged inserts/deletes written to a transaction's write-ahead marker,
+/// replayed verbatim by [`CollectionManager::commit`] and, if a crash left
+/// the marker behind, by [`CollectionManager::load_collection`].
+#[derive(Debug, Serialize, Deserialize)]
+struct WriteAheadBatch {
+    inserts: Vec<Vector>,
+    deletes: Vec<Uuid>,
+}
 
 fn get_current_timestamp() -> String {
     SystemTime::now()
@@ -15,22 +37,80 @@ fn get_current_timestamp() -> String {
         .to_string()
 }
 
+/// The on-disk files a backend reads/writes, relative to a collection (or
+/// snapshot) directory. Shared by collection creation, loading, and the
+/// snapshot/restore subsystem so all three agree on where each backend's
+/// data lives.
+fn backend_files(dir: &Path, kind: BackendKind) -> Vec<PathBuf> {
+    match kind {
+        BackendKind::SqliteBinary => {
+            let index_path = dir.join("vectors.kwi");
+
+            // `metadata.sqlite3` and `vectors.kwi` (the index header/footer)
+            // must come first: `load_collection`/`restore_collection` index
+            // into this vec by position. Everything after is the vector
+            // data itself: the segment files the header's entries point
+            // into (`vectors.000.kwi`, `vectors.001.kwi`, ...) and the
+            // index-log sidecar holding records not yet folded into the
+            // footer by a `flush()`. Without these a restored/snapshotted
+            // header just points at segment files that don't exist.
+            let mut files = vec![dir.join("metadata.sqlite3"), index_path.clone()];
+            files.extend(crate::binary_index::existing_segment_paths(&index_path));
+
+            let log_path = crate::binary_index::log_path(&index_path);
+            if log_path.exists() {
+                files.push(log_path);
+            }
+
+            files
+        }
+        BackendKind::Json => vec![
+            dir.join("vectors.json"),
+            dir.join("system_info.json"),
+        ],
+        BackendKind::InMemory => Vec::new(),
+    }
+}
+
 pub struct CollectionManager {
     base_path: std::path::PathBuf,
     collections: HashMap<String, Collection>,
 }
 
+/// What happened to one file during [`CollectionManager::import_dir`].
+#[derive(Debug)]
+pub enum ImportOutcome {
+    /// Stored as a new vector under this id.
+    Added(Uuid),
+    /// Its content hash matched a vector already in the collection, so it
+    /// was skipped in favor of the existing one (returned here).
+    SkippedDuplicate(Uuid),
+    /// Couldn't be read, parsed, or inserted; the message explains why.
+    Failed(String),
+}
+
+/// One file's result from [`CollectionManager::import_dir`].
+#[derive(Debug)]
+pub struct ImportEntry {
+    pub path: PathBuf,
+    pub outcome: ImportOutcome,
+}
+
 pub struct Collection {
     name: String,
-    pub sqlite_storage: SQLiteStorage,
-    binary_index: BinaryIndex,
+    backend: Box<dyn CollectionBackend>,
     dimension: usize,
+    /// Configured by `create_collection_with_embedder`; powers
+    /// `add_text`/`search_text`. Not persisted across process restarts —
+    /// a collection reopened via `get_collection` must be re-configured
+    /// with an embedder if text-based access is needed again.
+    embedder: Option<Box<dyn Embedder>>,
 }
 
 impl CollectionManager {
     pub fn new<P: AsRef<Path>>(base_path: P) -> Result<Self> {
         let base_path = base_path.as_ref().to_path_buf();
-        
+
         // Create base directory if it doesn't exist
         std::fs::create_dir_all(&base_path)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to create base directory: {}", e)))?;
@@ -41,29 +121,85 @@ impl CollectionManager {
         })
     }
 
+    /// Creates a collection using the default [`BackendKind::SqliteBinary`]
+    /// backend.
     pub fn create_collection(&mut self, name: &str, dimension: usize) -> Result<()> {
+        self.create_collection_internal(name, dimension, None, BackendKind::default())
+    }
+
+    /// Like [`Self::create_collection`], but lets the caller pick the
+    /// storage backend: the durable SQLite+binary hybrid, a pure in-memory
+    /// backend for tests/scratch collections, or a single portable JSON
+    /// file.
+    pub fn create_collection_with_backend(
+        &mut self,
+        name: &str,
+        dimension: usize,
+        backend_kind: BackendKind,
+    ) -> Result<()> {
+        self.create_collection_internal(name, dimension, None, backend_kind)
+    }
+
+    /// Like [`Self::create_collection`], but configures the collection with
+    /// an [`Embedder`] so `add_text`/`search_text` can be used to store and
+    /// search text directly instead of pre-computed vectors. Errors if the
+    /// embedder's output dimension doesn't match `dimension`.
+    pub fn create_collection_with_embedder(
+        &mut self,
+        name: &str,
+        dimension: usize,
+        embedder: Box<dyn Embedder>,
+    ) -> Result<()> {
+        if embedder.dimension() != dimension {
+            return Err(crate::VectorDBError::StorageError(format!(
+                "Embedder produces {}-dimensional vectors but collection '{}' is configured for dimension {}",
+                embedder.dimension(),
+                name,
+                dimension
+            )));
+        }
+
+        self.create_collection_internal(name, dimension, Some(embedder), BackendKind::default())
+    }
+
+    fn create_collection_internal(
+        &mut self,
+        name: &str,
+        dimension: usize,
+        embedder: Option<Box<dyn Embedder>>,
+        backend_kind: BackendKind,
+    ) -> Result<()> {
         let collection_path = self.base_path.join(name);
         std::fs::create_dir_all(&collection_path)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to create collection directory: {}", e)))?;
 
-        // Create SQLite database for metadata
-        let db_path = collection_path.join("metadata.sqlite3");
-        let sqlite_storage = SQLiteStorage::new(&db_path, name)?;
-
-        // Create binary index file for vectors
-        let index_path = collection_path.join("vectors.kwi");
-        let binary_index = BinaryIndex::new(&index_path, dimension)?;
+        let backend: Box<dyn CollectionBackend> = match backend_kind {
+            BackendKind::SqliteBinary => {
+                let db_path = collection_path.join("metadata.sqlite3");
+                let index_path = collection_path.join("vectors.kwi");
+                Box::new(SqliteBinaryBackend::new(&db_path, &index_path, name, dimension)?)
+            }
+            BackendKind::InMemory => Box::new(InMemoryBackend::new()),
+            BackendKind::Json => {
+                let files = backend_files(&collection_path, BackendKind::Json);
+                Box::new(JsonBackend::new(&files[0], &files[1])?)
+            }
+        };
 
         let collection = Collection {
             name: name.to_string(),
-            sqlite_storage,
-            binary_index,
+            backend,
             dimension,
+            embedder,
         };
 
         self.collections.insert(name.to_string(), collection);
 
+        std::fs::write(collection_path.join(BACKEND_MARKER_FILE), backend_kind.as_str())
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to record backend kind: {}", e)))?;
+
         // Set system info
+        self.set_system_info(name, "backend", backend_kind.as_str())?;
         self.set_system_info(name, "dimension", &dimension.to_string())?;
         self.set_system_info(name, "created_at", &get_current_timestamp())?;
 
@@ -85,40 +221,92 @@ impl CollectionManager {
             return Ok(());
         }
 
-        let db_path = collection_path.join("metadata.sqlite3");
-        let index_path = collection_path.join("vectors.kwi");
+        let backend_kind = std::fs::read_to_string(collection_path.join(BACKEND_MARKER_FILE))
+            .ok()
+            .and_then(|s| BackendKind::parse(s.trim()))
+            .unwrap_or(BackendKind::SqliteBinary);
 
-        if !db_path.exists() || !index_path.exists() {
+        let files = backend_files(&collection_path, backend_kind);
+        if !files.is_empty() && !files.iter().all(|f| f.exists()) {
             return Ok(());
         }
 
-        let sqlite_storage = SQLiteStorage::new(&db_path, name)?;
-        let binary_index = BinaryIndex::new(&index_path, 128)?; // Default dimension, will be updated
-        let dimension = binary_index.get_dimension();
+        let (backend, dimension): (Box<dyn CollectionBackend>, usize) = match backend_kind {
+            BackendKind::SqliteBinary => {
+                let backend = SqliteBinaryBackend::open(&files[0], &files[1], name)?;
+                let dimension = backend.binary_index.get_dimension();
+                (Box::new(backend), dimension)
+            }
+            BackendKind::Json => {
+                let backend = JsonBackend::new(&files[0], &files[1])?;
+                let dimension = backend
+                    .get_system_info("dimension")?
+                    .and_then(|d| d.parse().ok())
+                    .unwrap_or(0);
+                (Box::new(backend), dimension)
+            }
+            // Nothing persisted to reload: an in-memory collection simply
+            // doesn't survive past the process that created it.
+            BackendKind::InMemory => return Ok(()),
+        };
 
         let collection = Collection {
             name: name.to_string(),
-            sqlite_storage,
-            binary_index,
+            backend,
             dimension,
+            embedder: None,
         };
 
         self.collections.insert(name.to_string(), collection);
+        self.replay_pending_write_ahead_batches(name, &collection_path)?;
+
+        Ok(())
+    }
+
+    /// Finds any `.wal-<epoch>` markers left behind by a transaction whose
+    /// `commit` never finished (e.g. the process crashed mid-apply), and
+    /// replays them in epoch order before the collection is handed back to
+    /// callers. Replaying is safe to repeat: inserts dedup by content hash
+    /// and deletes are idempotent, so a marker that was actually fully
+    /// applied before the crash just re-applies as a no-op.
+    fn replay_pending_write_ahead_batches(&mut self, name: &str, collection_path: &Path) -> Result<()> {
+        let mut markers: Vec<(u64, PathBuf)> = std::fs::read_dir(collection_path)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read collection directory: {}", e)))?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let epoch: u64 = path.file_name()?.to_str()?.strip_prefix(WAL_MARKER_PREFIX)?.parse().ok()?;
+                Some((epoch, path))
+            })
+            .collect();
+        markers.sort_by_key(|(epoch, _)| *epoch);
+
+        for (_, marker_path) in markers {
+            let contents = std::fs::read_to_string(&marker_path)
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read write-ahead marker: {}", e)))?;
+            let batch: WriteAheadBatch = serde_json::from_str(&contents)
+                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse write-ahead marker: {}", e)))?;
+
+            self.apply_write_ahead_batch(name, &batch)?;
+
+            std::fs::remove_file(&marker_path)
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to remove write-ahead marker: {}", e)))?;
+        }
 
         Ok(())
     }
 
     pub fn list_collections(&self) -> Result<Vec<String>> {
         let mut collections = Vec::new();
-        
+
         for entry in std::fs::read_dir(&self.base_path)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read base directory: {}", e)))? {
             let entry = entry
                 .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read directory entry: {}", e)))?;
-            
+
             if entry.file_type()?.is_dir() {
                 let name = entry.file_name().to_string_lossy().to_string();
-                if self.base_path.join(&name).join("metadata.sqlite3").exists() {
+                if self.base_path.join(&name).join(BACKEND_MARKER_FILE).exists() {
                     collections.push(name);
                 }
             }
@@ -139,55 +327,131 @@ impl CollectionManager {
         Ok(())
     }
 
-    pub fn add_vector(&mut self, collection_name: &str, vector: &Vector) -> Result<()> {
+    /// Inserts `vector`, unless an identical payload (by
+    /// [`Vector::content_hash`]) is already stored, in which case the
+    /// existing vector's id is returned instead of writing a duplicate.
+    pub fn add_vector(&mut self, collection_name: &str, vector: &Vector) -> Result<Uuid> {
         let collection = self.get_collection(collection_name)?
             .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
 
         // Validate dimension
         if vector.dimension() != collection.dimension {
             return Err(crate::VectorDBError::StorageError(
-                format!("Vector dimension {} doesn't match collection dimension {}", 
+                format!("Vector dimension {} doesn't match collection dimension {}",
                     vector.dimension(), collection.dimension)
             ));
         }
 
-        // Store in SQLite for metadata and system info
-        collection.sqlite_storage.insert_vector(vector)?;
+        let hash = vector.content_hash();
+        if let Some(existing_id) = collection.backend.lookup_by_hash(hash)? {
+            return Ok(existing_id);
+        }
 
-        // Store in binary index for fast retrieval
-        collection.binary_index.add_vector(vector)?;
+        collection.backend.insert(vector)?;
+        collection.backend.record_hash(hash, &vector.id)?;
 
         // Update system info
-        let count = collection.binary_index.count_vectors();
+        let count = collection.backend.count()?;
         self.set_system_info(collection_name, "vector_count", &count.to_string())?;
         self.set_system_info(collection_name, "updated_at", &get_current_timestamp())?;
 
-        Ok(())
+        Ok(vector.id)
     }
 
-    pub fn get_vector(&self, collection_name: &str, id: &Uuid) -> Result<Option<Vector>> {
+    /// Embeds `text` with the collection's configured [`Embedder`], stores
+    /// the result with `text` folded into its metadata under `"text"`, and
+    /// returns the new document's ID. Errors if the collection has no
+    /// embedder configured.
+    pub fn add_text(
+        &mut self,
+        collection_name: &str,
+        text: &str,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<Uuid> {
+        let embedding = self.embed_text(collection_name, text)?;
+
+        let mut doc_metadata = metadata.unwrap_or_else(|| serde_json::json!({}));
+        if let serde_json::Value::Object(ref mut map) = doc_metadata {
+            map.insert("text".to_string(), serde_json::Value::String(text.to_string()));
+        }
+
+        let vector = Vector::with_metadata(embedding, doc_metadata);
+        self.add_vector(collection_name, &vector)
+    }
+
+    /// Walks `dir` recursively, deserializing every `*.json` file as a
+    /// [`Vector`] and inserting it into `collection_name` via
+    /// [`Self::add_vector`] (so content-hash dedup applies automatically).
+    /// A bad file never aborts the whole import: each file's outcome —
+    /// added, skipped as a duplicate, or failed to read/parse/insert — is
+    /// reported back, the way a file-store indexer reports per-file results.
+    pub fn import_dir(&mut self, collection_name: &str, dir: &Path) -> Result<Vec<ImportEntry>> {
+        let mut paths = Vec::new();
+        collect_json_files(dir, &mut paths)?;
+        paths.sort();
+
+        let mut entries = Vec::with_capacity(paths.len());
+        for path in paths {
+            let outcome = match import_file(self, collection_name, &path) {
+                Ok(outcome) => outcome,
+                Err(e) => ImportOutcome::Failed(e.to_string()),
+            };
+            entries.push(ImportEntry { path, outcome });
+        }
+
+        Ok(entries)
+    }
+
+    /// Embeds `text` with the collection's configured [`Embedder`] and
+    /// returns the `k` most similar stored documents, ranked by cosine
+    /// similarity (highest first).
+    pub fn search_text(&self, collection_name: &str, text: &str, k: usize) -> Result<Vec<(Vector, f32)>> {
+        let query_embedding = self.embed_text(collection_name, text)?;
+
+        let mut scored: Vec<(Vector, f32)> = self
+            .get_all_vectors(collection_name)?
+            .into_iter()
+            .map(|vector| {
+                let score = crate::utils::cosine_similarity(&query_embedding, &vector.data);
+                (vector, score)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+
+        Ok(scored)
+    }
+
+    fn embed_text(&self, collection_name: &str, text: &str) -> Result<ndarray::Array1<f32>> {
         let collection = self.collections.get(collection_name)
             .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
 
-        // Try binary index first (faster)
-        if let Some(vector) = collection.binary_index.get_vector(id)? {
-            return Ok(Some(vector));
-        }
+        let embedder = collection.embedder.as_ref().ok_or_else(|| {
+            crate::VectorDBError::StorageError(format!(
+                "Collection '{}' has no embedder configured; use CollectionManager::create_collection_with_embedder",
+                collection_name
+            ))
+        })?;
 
-        // Fallback to SQLite
-        collection.sqlite_storage.get_vector(id)
+        embedder.embed(text)
+    }
+
+    pub fn get_vector(&self, collection_name: &str, id: &Uuid) -> Result<Option<Vector>> {
+        let collection = self.collections.get(collection_name)
+            .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
+
+        collection.backend.get(id)
     }
 
     pub fn delete_vector(&mut self, collection_name: &str, id: &Uuid) -> Result<()> {
         let collection = self.get_collection(collection_name)?
             .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
 
-        // Delete from both storages
-        collection.sqlite_storage.delete_vector(id)?;
-        collection.binary_index.delete_vector(id)?;
+        collection.backend.delete(id)?;
 
         // Update system info
-        let count = collection.binary_index.count_vectors();
+        let count = collection.backend.count()?;
         self.set_system_info(collection_name, "vector_count", &count.to_string())?;
         self.set_system_info(collection_name, "updated_at", &get_current_timestamp())?;
 
@@ -198,15 +462,14 @@ impl CollectionManager {
         let collection = self.collections.get(collection_name)
             .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
 
-        // Use binary index for better performance
-        collection.binary_index.get_all_vectors()
+        collection.backend.all_vectors()
     }
 
     pub fn count_vectors(&self, collection_name: &str) -> Result<usize> {
         let collection = self.collections.get(collection_name)
             .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
 
-        Ok(collection.binary_index.count_vectors())
+        collection.backend.count()
     }
 
     pub fn get_collection_info(&self, collection_name: &str) -> Result<HashMap<String, String>> {
@@ -216,11 +479,12 @@ impl CollectionManager {
         let mut info = HashMap::new();
         info.insert("name".to_string(), collection.name.clone());
         info.insert("dimension".to_string(), collection.dimension.to_string());
-        info.insert("vector_count".to_string(), collection.binary_index.count_vectors().to_string());
+        info.insert("vector_count".to_string(), collection.backend.count()?.to_string());
+        info.insert("backend".to_string(), collection.backend.kind().as_str().to_string());
 
-        // Get system info from SQLite
-        for key in ["created_at", "updated_at", "dimension"] {
-            if let Some(value) = collection.sqlite_storage.get_system_info(key)? {
+        // Get system info from the backend
+        for key in ["created_at", "updated_at", "dimension", "backend"] {
+            if let Some(value) = collection.backend.get_system_info(key)? {
                 info.insert(key.to_string(), value);
             }
         }
@@ -228,19 +492,442 @@ impl CollectionManager {
         Ok(info)
     }
 
-    pub fn set_system_info(&self, collection_name: &str, key: &str, value: &str) -> Result<()> {
+    pub fn set_system_info(&mut self, collection_name: &str, key: &str, value: &str) -> Result<()> {
+        let collection = self.collections.get_mut(collection_name)
+            .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
+
+        collection.backend.set_system_info(key, value)
+    }
+
+    pub fn get_system_info(&self, collection_name: &str, key: &str) -> Result<Option<String>> {
         let collection = self.collections.get(collection_name)
             .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
 
-        collection.sqlite_storage.set_system_info(key, value)
+        collection.backend.get_system_info(key)
     }
 
+    /// Historical alias for [`Self::vacuum_collection`], kept so existing
+    /// callers don't have to change; prefer `vacuum_collection` in new code
+    /// since it also records freed-space stats in system info.
     pub fn optimize_collection(&mut self, collection_name: &str) -> Result<()> {
+        self.vacuum_collection(collection_name)
+    }
+
+    /// Combines exact cosine similarity over every stored vector with a BM25 keyword score over each
+    /// vector's stringified metadata, so a caller can ask for "near X whose
+    /// metadata mentions Y" in one call. Both score sets are min-max
+    /// normalized to `[0, 1]` before being fused as
+    /// `alpha * vec_score + (1 - alpha) * text_score`, and the top `k`
+    /// fused results are returned highest-first.
+    pub fn hybrid_search(
+        &self,
+        collection_name: &str,
+        query_vector: &ndarray::Array1<f32>,
+        text_query: &str,
+        k: usize,
+        alpha: f32,
+    ) -> Result<Vec<(Vector, f32)>> {
+        let vectors = self.get_all_vectors(collection_name)?;
+        if vectors.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let vec_scores: HashMap<Uuid, f32> = vectors
+            .iter()
+            .map(|v| (v.id, crate::utils::cosine_similarity(query_vector, &v.data)))
+            .collect();
+
+        let corpus: Vec<(Uuid, Vec<String>)> = vectors
+            .iter()
+            .map(|v| (v.id, tokenize(&metadata_to_text(&v.metadata))))
+            .collect();
+        let query_terms = tokenize(text_query);
+        let text_scores = bm25_scores(&corpus, &query_terms);
+
+        let normalized_vec = min_max_normalize(&vec_scores);
+        let normalized_text = min_max_normalize(&text_scores);
+
+        let mut fused: Vec<(Vector, f32)> = vectors
+            .into_iter()
+            .map(|v| {
+                let vec_score = normalized_vec.get(&v.id).copied().unwrap_or(0.0);
+                let text_score = normalized_text.get(&v.id).copied().unwrap_or(0.0);
+                let fused_score = alpha * vec_score + (1.0 - alpha) * text_score;
+                (v, fused_score)
+            })
+            .collect();
+
+        fused.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        fused.truncate(k);
+
+        Ok(fused)
+    }
+
+    /// Flushes the collection's backend, then copies its on-disk files into
+    /// a timestamped, content-hashed subdirectory of `out_dir`, the way a
+    /// generational backup tool names its snapshots. Errors if the
+    /// collection's backend (e.g. [`BackendKind::InMemory`]) has nothing on
+    /// disk to snapshot. Returns the created directory so callers can hand
+    /// it straight to [`Self::restore_collection`].
+    pub fn snapshot_collection(&mut self, collection_name: &str, out_dir: &Path) -> Result<PathBuf> {
+        let collection = self.get_collection(collection_name)?
+            .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
+
+        collection.backend.flush()?;
+        let backend_kind = collection.backend.kind();
+
+        let collection_path = self.base_path.join(collection_name);
+        let source_files = backend_files(&collection_path, backend_kind);
+        if source_files.is_empty() {
+            return Err(crate::VectorDBError::StorageError(format!(
+                "Collection '{}' uses an in-memory backend and has nothing on disk to snapshot",
+                collection_name
+            )));
+        }
+
+        let mut hash_input = Vec::new();
+        for file in &source_files {
+            hash_input.extend_from_slice(&std::fs::read(file)
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read '{}' for snapshot: {}", file.display(), e)))?);
+        }
+        let content_hash = xxhash_rust::xxh3::xxh3_64(&hash_input);
+
+        let snapshot_dir = out_dir.join(format!(
+            "{}-{}-{:016x}",
+            collection_name,
+            get_current_timestamp(),
+            content_hash
+        ));
+        std::fs::create_dir_all(&snapshot_dir)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to create snapshot directory: {}", e)))?;
+
+        for file in &source_files {
+            let file_name = file.file_name().expect("backend_files entries always have a file name");
+            std::fs::copy(file, snapshot_dir.join(file_name))
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to copy '{}' into snapshot: {}", file.display(), e)))?;
+        }
+        std::fs::write(snapshot_dir.join(BACKEND_MARKER_FILE), backend_kind.as_str())
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to record backend kind in snapshot: {}", e)))?;
+
+        self.set_system_info(collection_name, "last_snapshot_at", &get_current_timestamp())?;
+        self.set_system_info(collection_name, "last_snapshot_dir", &snapshot_dir.to_string_lossy())?;
+
+        Ok(snapshot_dir)
+    }
+
+    /// Rolls `collection_name` back to a directory produced by
+    /// [`Self::snapshot_collection`], replacing its current on-disk files
+    /// wholesale. The in-memory collection (if loaded) is dropped first so
+    /// its next use re-opens the restored files instead of flushing stale
+    /// state over them.
+    pub fn restore_collection(&mut self, collection_name: &str, snapshot_dir: &Path) -> Result<()> {
+        let backend_kind = std::fs::read_to_string(snapshot_dir.join(BACKEND_MARKER_FILE))
+            .ok()
+            .and_then(|s| BackendKind::parse(s.trim()))
+            .unwrap_or(BackendKind::SqliteBinary);
+
+        let source_files = backend_files(snapshot_dir, backend_kind);
+        if source_files.is_empty() || !source_files.iter().all(|f| f.exists()) {
+            return Err(crate::VectorDBError::PersistenceError(format!(
+                "Snapshot directory '{}' is missing the files expected for a {:?} backend",
+                snapshot_dir.display(),
+                backend_kind
+            )));
+        }
+
+        self.collections.remove(collection_name);
+
+        let collection_path = self.base_path.join(collection_name);
+        std::fs::create_dir_all(&collection_path)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to create collection directory: {}", e)))?;
+
+        for file in &source_files {
+            let file_name = file.file_name().expect("backend_files entries always have a file name");
+            std::fs::copy(file, collection_path.join(file_name))
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to restore '{}': {}", file.display(), e)))?;
+        }
+        std::fs::write(collection_path.join(BACKEND_MARKER_FILE), backend_kind.as_str())
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to record backend kind: {}", e)))?;
+
+        self.load_collection(collection_name)?;
+        self.set_system_info(collection_name, "restored_at", &get_current_timestamp())?;
+        self.set_system_info(collection_name, "restored_from", &snapshot_dir.to_string_lossy())?;
+
+        Ok(())
+    }
+
+    /// Reclaims space freed by prior deletes and records how much was
+    /// recovered in system info. For the SQLite+binary backend this rewrites
+    /// the `.kwi` file dropping tombstoned slots; backends without anything
+    /// to reclaim report zero via [`CollectionBackend::vacuum`]'s default.
+    pub fn vacuum_collection(&mut self, collection_name: &str) -> Result<()> {
         let collection = self.get_collection(collection_name)?
             .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
 
-        collection.binary_index.optimize()?;
+        let stats = collection.backend.vacuum()?;
+
+        self.set_system_info(collection_name, "last_vacuum_at", &get_current_timestamp())?;
+        self.set_system_info(collection_name, "last_vacuum_reclaimed_bytes", &stats.reclaimed_bytes.to_string())?;
+        self.set_system_info(collection_name, "last_vacuum_live_vectors", &stats.live_vectors.to_string())?;
+        self.set_system_info(collection_name, "last_vacuum_dead_vectors", &stats.dead_vectors.to_string())?;
 
         Ok(())
     }
-} 
\ No newline at end of file
+
+    /// Opens a [`Transaction`] against `collection_name`: a buffered batch
+    /// of inserts/deletes that only takes effect when passed to
+    /// [`Self::commit`]. The transaction's own reads see a repeatable
+    /// snapshot taken right now, layered with its own not-yet-committed
+    /// writes; every other reader keeps seeing the last committed state
+    /// until `commit` runs.
+    pub fn begin(&mut self, collection_name: &str) -> Result<Transaction> {
+        self.get_collection(collection_name)?
+            .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
+
+        let snapshot = self.get_all_vectors(collection_name)?;
+        let epoch = self.next_epoch(collection_name)?;
+
+        Ok(Transaction {
+            collection_name: collection_name.to_string(),
+            epoch,
+            snapshot,
+            staged_inserts: HashMap::new(),
+            staged_deletes: HashSet::new(),
+        })
+    }
+
+    /// Applies `txn`'s staged inserts/deletes atomically: a write-ahead
+    /// marker recording the batch is written first, the batch is applied to
+    /// the backend (for the SQLite+binary backend the SQLite deletes and
+    /// inserts each run in their own SQL transaction, not one shared one),
+    /// and the marker is removed only once that succeeds. If the process
+    /// dies between those two steps, the marker is replayed by
+    /// `load_collection` the next time the collection is opened.
+    pub fn commit(&mut self, txn: Transaction) -> Result<()> {
+        let batch = WriteAheadBatch {
+            inserts: txn.staged_inserts.into_values().collect(),
+            deletes: txn.staged_deletes.into_iter().collect(),
+        };
+
+        let collection_path = self.base_path.join(&txn.collection_name);
+        let marker_path = collection_path.join(format!("{}{}", WAL_MARKER_PREFIX, txn.epoch));
+        let marker_json = serde_json::to_string(&batch)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize write-ahead batch: {}", e)))?;
+        std::fs::write(&marker_path, marker_json)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write write-ahead marker: {}", e)))?;
+
+        self.apply_write_ahead_batch(&txn.collection_name, &batch)?;
+
+        // Marker file is only meaningful while a batch might still be
+        // partially applied; `apply_write_ahead_batch` having returned
+        // means it fully landed, so it can go.
+        if marker_path.exists() {
+            std::fs::remove_file(&marker_path)
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to remove write-ahead marker: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Discards `txn`'s staged writes; the collection is left exactly as it
+    /// was before `begin`.
+    pub fn rollback(&mut self, _txn: Transaction) {}
+
+    fn apply_write_ahead_batch(&mut self, collection_name: &str, batch: &WriteAheadBatch) -> Result<()> {
+        let collection = self.get_collection(collection_name)?
+            .ok_or_else(|| crate::VectorDBError::StorageError(format!("Collection '{}' not found", collection_name)))?;
+
+        collection.backend.apply_batch(&batch.inserts, &batch.deletes)?;
+        collection.backend.flush()?;
+
+        let count = self.count_vectors(collection_name)?;
+        self.set_system_info(collection_name, "vector_count", &count.to_string())?;
+        self.set_system_info(collection_name, "updated_at", &get_current_timestamp())?;
+
+        Ok(())
+    }
+
+    /// Advances and persists `collection_name`'s transaction epoch counter,
+    /// tagging each [`Transaction`] with a number that only ever increases
+    /// (including across process restarts), so write-ahead markers replay
+    /// in the order their transactions were opened.
+    fn next_epoch(&mut self, collection_name: &str) -> Result<u64> {
+        let current = self.get_system_info(collection_name, "epoch")?
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+        let next = current + 1;
+        self.set_system_info(collection_name, "epoch", &next.to_string())?;
+        Ok(next)
+    }
+}
+
+/// A buffered batch of inserts/deletes against one collection, opened by
+/// [`CollectionManager::begin`]. Nothing here is visible to any other reader
+/// of the collection until the transaction is passed to
+/// [`CollectionManager::commit`]; dropping it without committing (or calling
+/// [`CollectionManager::rollback`]) discards the batch.
+pub struct Transaction {
+    collection_name: String,
+    epoch: u64,
+    snapshot: Vec<Vector>,
+    staged_inserts: HashMap<Uuid, Vector>,
+    staged_deletes: HashSet<Uuid>,
+}
+
+impl Transaction {
+    /// Stages `vector` for insertion on commit.
+    pub fn insert(&mut self, vector: Vector) {
+        self.staged_deletes.remove(&vector.id);
+        self.staged_inserts.insert(vector.id, vector);
+    }
+
+    /// Stages `id` for deletion on commit.
+    pub fn delete(&mut self, id: Uuid) {
+        self.staged_inserts.remove(&id);
+        self.staged_deletes.insert(id);
+    }
+
+    /// Reads `id` as this transaction would see it: its own staged
+    /// inserts/deletes layered over the snapshot taken at `begin`, ignoring
+    /// anything committed by another transaction since.
+    pub fn get(&self, id: &Uuid) -> Option<Vector> {
+        if self.staged_deletes.contains(id) {
+            return None;
+        }
+        if let Some(vector) = self.staged_inserts.get(id) {
+            return Some(vector.clone());
+        }
+        self.snapshot.iter().find(|v| &v.id == id).cloned()
+    }
+
+    /// All vectors as this transaction would see them: the `begin`-time
+    /// snapshot with staged deletes removed and staged inserts layered on.
+    pub fn all_vectors(&self) -> Vec<Vector> {
+        let mut result: Vec<Vector> = self.snapshot.iter()
+            .filter(|v| !self.staged_deletes.contains(&v.id) && !self.staged_inserts.contains_key(&v.id))
+            .cloned()
+            .collect();
+        result.extend(self.staged_inserts.values().cloned());
+        result
+    }
+
+    /// This transaction's epoch, the monotonically increasing id its
+    /// write-ahead marker is filed under.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+}
+
+/// Recursively collects every `*.json` file under `dir` into `out`.
+fn collect_json_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)
+        .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read directory '{}': {}", dir.display(), e)))? {
+        let entry = entry
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read directory entry: {}", e)))?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_json_files(&path, out)?;
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            out.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads and parses a single vector file for [`CollectionManager::import_dir`],
+/// then inserts it, reporting whether it was newly added or deduped away.
+fn import_file(manager: &mut CollectionManager, collection_name: &str, path: &Path) -> Result<ImportOutcome> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read '{}': {}", path.display(), e)))?;
+    let vector: Vector = serde_json::from_str(&contents)
+        .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse '{}': {}", path.display(), e)))?;
+
+    let stored_id = manager.add_vector(collection_name, &vector)?;
+    if stored_id == vector.id {
+        Ok(ImportOutcome::Added(stored_id))
+    } else {
+        Ok(ImportOutcome::SkippedDuplicate(stored_id))
+    }
+}
+
+/// Lowercases and splits on anything that isn't alphanumeric, matching the
+/// simple whitespace/punctuation tokenization `hybrid_search`'s BM25 pass
+/// expects from both documents and the query.
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Flattens a vector's JSON metadata into one string so it can be tokenized
+/// and scored like a document's text.
+fn metadata_to_text(metadata: &Option<serde_json::Value>) -> String {
+    match metadata {
+        Some(serde_json::Value::Object(map)) => map
+            .values()
+            .map(|value| match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Okapi BM25 with the usual `k1 = 1.2`, `b = 0.75` defaults, scoring every
+/// document in `corpus` against `query_terms`.
+fn bm25_scores(corpus: &[(Uuid, Vec<String>)], query_terms: &[String]) -> HashMap<Uuid, f32> {
+    const K1: f32 = 1.2;
+    const B: f32 = 0.75;
+
+    let n = corpus.len() as f32;
+    let avg_len = if corpus.is_empty() {
+        0.0
+    } else {
+        corpus.iter().map(|(_, tokens)| tokens.len()).sum::<usize>() as f32 / n
+    };
+
+    let mut scores = HashMap::new();
+    for (id, tokens) in corpus {
+        let doc_len = tokens.len() as f32;
+        let mut score = 0.0f32;
+
+        for term in query_terms {
+            let tf = tokens.iter().filter(|t| *t == term).count() as f32;
+            if tf == 0.0 {
+                continue;
+            }
+
+            let df = corpus.iter().filter(|(_, t)| t.iter().any(|w| w == term)).count() as f32;
+            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+            let denom = tf + K1 * (1.0 - B + B * doc_len / avg_len.max(f32::EPSILON));
+            score += idf * (tf * (K1 + 1.0)) / denom;
+        }
+
+        scores.insert(*id, score);
+    }
+
+    scores
+}
+
+/// Rescales `scores` into `[0, 1]`; a constant score set maps every entry to
+/// `0.0` since there's no relative ordering to preserve.
+fn min_max_normalize(scores: &HashMap<Uuid, f32>) -> HashMap<Uuid, f32> {
+    let min = scores.values().cloned().fold(f32::INFINITY, f32::min);
+    let max = scores.values().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let range = max - min;
+
+    scores
+        .iter()
+        .map(|(id, score)| {
+            let normalized = if range > f32::EPSILON { (score - min) / range } else { 0.0 };
+            (*id, normalized)
+        })
+        .collect()
+}
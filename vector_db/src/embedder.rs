@@ -0,0 +1,71 @@
+use crate::utils::normalize_vector;
+use crate::Result;
+use ndarray::Array1;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Produces a fixed-dimension embedding for arbitrary text. Object-safe so
+/// callers can plug a remote model backend in behind a `Box<dyn Embedder>`
+/// instead of being limited to the built-in [`HashedNgramEmbedder`].
+pub trait Embedder {
+    fn embed(&self, text: &str) -> Result<Array1<f32>>;
+
+    /// The fixed dimension of vectors this embedder produces, so callers
+    /// can validate it against a collection's configured dimension before
+    /// any text is embedded.
+    fn dimension(&self) -> usize;
+}
+
+/// A deterministic, dependency-free embedder: hashes character n-grams into
+/// fixed-size buckets (the "hashing trick") and counts them, producing a
+/// bag-of-n-grams vector. Same text always maps to the same vector, with no
+/// external model or service required.
+#[derive(Debug, Clone)]
+pub struct HashedNgramEmbedder {
+    dimension: usize,
+    ngram_size: usize,
+}
+
+impl HashedNgramEmbedder {
+    pub fn new(dimension: usize) -> Self {
+        Self::with_ngram_size(dimension, 3)
+    }
+
+    pub fn with_ngram_size(dimension: usize, ngram_size: usize) -> Self {
+        Self {
+            dimension,
+            ngram_size: ngram_size.max(1),
+        }
+    }
+}
+
+impl Embedder for HashedNgramEmbedder {
+    fn embed(&self, text: &str) -> Result<Array1<f32>> {
+        let mut buckets = vec![0f32; self.dimension];
+        let normalized = text.to_lowercase();
+        let chars: Vec<char> = normalized.chars().collect();
+
+        if chars.is_empty() {
+            return Ok(Array1::from_vec(buckets));
+        }
+
+        let window_size = self.ngram_size.min(chars.len());
+        for window in chars.windows(window_size) {
+            let ngram: String = window.iter().collect();
+            let bucket = (hash_str(&ngram) as usize) % self.dimension;
+            buckets[bucket] += 1.0;
+        }
+
+        Ok(normalize_vector(&Array1::from_vec(buckets)))
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+fn hash_str(s: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
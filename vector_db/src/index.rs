@@ -2,6 +2,8 @@ use uuid::Uuid;
 use crate::Result;
 use ndarray::Array1;
 use crate::utils::{cosine_similarity, euclidean_distance};
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
 
 pub trait Index {
     fn build(&mut self, vectors: &[(&Uuid, &Array1<f32>)]) -> Result<()>;
@@ -68,4 +70,120 @@ impl Default for BruteForceIndex {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+}
+
+/// Samples one standard-normal value via the Box-Muller transform, so
+/// `LshIndex` can draw hyperplanes from `N(0, 1)` without an extra
+/// distribution crate dependency.
+fn sample_standard_normal(rng: &mut impl Rng) -> f32 {
+    let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.gen_range(0.0..1.0);
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+}
+
+/// Cosine-LSH approximate nearest-neighbour index using `L` independent
+/// hash tables of `k` random hyperplanes each. A vector's `k`-bit signature
+/// in a table is the sign pattern of its dot product against that table's
+/// hyperplanes; vectors sharing a signature in any table are candidates.
+/// Larger `k` makes buckets more selective (fewer, more precise candidates);
+/// larger `l` makes it more likely a true neighbour shares a bucket with the
+/// query in at least one table, trading index size and query cost for
+/// recall.
+pub struct LshIndex {
+    k: usize,
+    l: usize,
+    tables: Vec<Vec<Array1<f32>>>,
+    buckets: Vec<HashMap<u64, Vec<Uuid>>>,
+    all_vectors: HashMap<Uuid, Array1<f32>>,
+}
+
+impl LshIndex {
+    /// Create a new index with `l` hash tables of `k` hyperplanes each.
+    pub fn new(k: usize, l: usize) -> Self {
+        Self {
+            k,
+            l,
+            tables: Vec::new(),
+            buckets: Vec::new(),
+            all_vectors: HashMap::new(),
+        }
+    }
+
+    fn signature(&self, table: &[Array1<f32>], vector: &Array1<f32>) -> u64 {
+        let mut signature: u64 = 0;
+        for (i, plane) in table.iter().enumerate() {
+            if vector.dot(plane) >= 0.0 {
+                signature |= 1 << i;
+            }
+        }
+        signature
+    }
+}
+
+impl Index for LshIndex {
+    fn build(&mut self, vectors: &[(&Uuid, &Array1<f32>)]) -> Result<()> {
+        self.clear();
+
+        if vectors.is_empty() {
+            return Ok(());
+        }
+
+        let dim = vectors[0].1.len();
+        let mut rng = rand::thread_rng();
+        self.tables = (0..self.l)
+            .map(|_| {
+                (0..self.k)
+                    .map(|_| Array1::from((0..dim).map(|_| sample_standard_normal(&mut rng)).collect::<Vec<f32>>()))
+                    .collect()
+            })
+            .collect();
+        self.buckets = vec![HashMap::new(); self.l];
+
+        for (id, vector) in vectors {
+            let vector = (*vector).clone();
+            for (table_idx, table) in self.tables.iter().enumerate() {
+                let signature = self.signature(table, &vector);
+                self.buckets[table_idx]
+                    .entry(signature)
+                    .or_insert_with(Vec::new)
+                    .push(**id);
+            }
+            self.all_vectors.insert(**id, vector);
+        }
+
+        Ok(())
+    }
+
+    fn query(&self, query: &Array1<f32>, top_k: usize) -> Vec<(Uuid, f32)> {
+        let mut candidates: HashSet<Uuid> = HashSet::new();
+        for (table_idx, table) in self.tables.iter().enumerate() {
+            let signature = self.signature(table, query);
+            if let Some(bucket) = self.buckets[table_idx].get(&signature) {
+                candidates.extend(bucket.iter().copied());
+            }
+        }
+
+        // Buckets can be sparse (or the index empty), in which case we
+        // simply return fewer than `top_k` rather than over-fetching
+        // elsewhere in the index.
+        let mut results: Vec<(Uuid, f32)> = candidates
+            .into_iter()
+            .filter_map(|id| self.all_vectors.get(&id).map(|v| (id, cosine_similarity(query, v))))
+            .collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(top_k);
+        results
+    }
+
+    fn clear(&mut self) {
+        self.tables.clear();
+        self.buckets.clear();
+        self.all_vectors.clear();
+    }
+}
+
+impl Default for LshIndex {
+    fn default() -> Self {
+        Self::new(12, 8)
+    }
+}
\ No newline at end of file
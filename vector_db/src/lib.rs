@@ -6,7 +6,12 @@ pub mod persistence;
 pub mod utils;
 pub mod sqlite_storage;
 pub mod binary_index;
+pub mod backend;
 pub mod collection_manager;
+pub mod local_storage;
+pub mod embedder;
+pub mod async_local_storage;
+pub mod async_storage;
 
 use thiserror::Error;
 
@@ -20,19 +25,34 @@ pub enum VectorDBError {
     PersistenceError(String),
     #[error("Serialization Error: {0}")]
     SerializationError(String),
+    #[error("Integrity Error: {0}")]
+    IntegrityError(String),
+    #[error("Corruption Error: {0}")]
+    CorruptionError(String),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
+impl From<std::io::Error> for VectorDBError {
+    fn from(err: std::io::Error) -> Self {
+        VectorDBError::PersistenceError(err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, VectorDBError>;
 
 // Re-export main types for convenience
-pub use storage::{Storage, InMemoryStorage};
-pub use index::{Index, BruteForceIndex};
-pub use query::QueryEngine;
+pub use storage::{Storage, InMemoryStorage, BucketMapStorage, BucketMapConfig};
+pub use index::{Index, BruteForceIndex, LshIndex};
+pub use query::{QueryEngine, AsyncQueryEngine, ScoreDetail};
 pub use vector::Vector;
 pub use persistence::PersistentStorage;
 pub use utils::{cosine_similarity, euclidean_distance};
-pub use sqlite_storage::SQLiteStorage;
-pub use binary_index::BinaryIndex;
-pub use collection_manager::{CollectionManager, Collection}; 
\ No newline at end of file
+pub use sqlite_storage::{PooledSQLiteStorage, SQLiteStorage};
+pub use binary_index::{BinaryIndex, BinaryIndexConfig, BinaryIndexOpener, CompactionStats, VerifyReport};
+pub use backend::{BackendKind, CollectionBackend};
+pub use collection_manager::{CollectionManager, Collection, ImportEntry, ImportOutcome, Transaction};
+pub use local_storage::{LocalStorage, DedupStats, IntegrityReport};
+pub use embedder::{Embedder, HashedNgramEmbedder};
+pub use async_local_storage::AsyncLocalStorage;
+pub use async_storage::{AsyncStorage, InMemoryAsyncStorage, RedbStorage};
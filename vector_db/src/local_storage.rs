@@ -1,4 +1,5 @@
-use crate::{vector::Vector, Result};
+use crate::{embedder::Embedder, vector::Vector, Result};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write, Seek, SeekFrom};
@@ -8,24 +9,259 @@ use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use bincode;
 use serde_json;
 
-const KWI_MAGIC: &[u8; 4] = b"KWI\0";
-const KWI_VERSION: u32 = 1;
+pub(crate) const KWI_MAGIC: &[u8; 4] = b"KWI\0";
+/// v1: id_len+id+data_len+data+metadata_len+metadata, no tombstone flag.
+/// v2: adds a 1-byte tombstone flag before each record, and repurposes the
+/// header's reserved slot as a live deleted-record count.
+/// v3: the former tombstone byte becomes a record-kind byte — 0 = live,
+/// 1 = tombstoned, 2 = reference (content-deduplicated; stores a target ID
+/// instead of its own data), 3 = payload-only (a former live record kept
+/// physically around only because a reference still points at it).
+/// v4 (current): appends a trailing CRC32 checksum (over the id+data/target+
+/// metadata bytes) after every record's body, so a truncated append or a
+/// flipped bit surfaces as a named `VectorDBError::CorruptionError` at the
+/// exact record offset instead of a confusing deserialization failure.
+/// Record bytes before the checksum are otherwise unchanged, so a v2 file
+/// (which only ever contains kind 0/1) parses as a v3 file without
+/// rewriting; v3 is migrated to v4 by `upgrade()` resolving every content
+/// reference and rewriting the file with checksums attached.
+pub(crate) const KWI_VERSION: u32 = 4;
 const STORAGE_DIR: &str = ".vector_storage";
 
-#[derive(Debug)]
+/// magic(4) + version(4) + total_count(8) + deleted_count(4).
+pub(crate) const HEADER_SIZE: u64 = 4 + 4 + 8 + 4;
+
+/// Once the ratio of tombstoned-to-total records crosses this threshold,
+/// `delete_vector` triggers a `compact()` automatically.
+const DEFAULT_COMPACT_THRESHOLD: f64 = 0.3;
+
+/// Record-kind byte stored where earlier versions kept a plain tombstone flag.
+const RECORD_LIVE: u8 = 0;
+const RECORD_TOMBSTONED: u8 = 1;
+const RECORD_REFERENCE: u8 = 2;
+const RECORD_PAYLOAD_ONLY: u8 = 3;
+
+/// xxh3_64 of a vector's bincode-serialized `data`, used by `add_vector` to
+/// detect an already-stored identical payload.
+fn content_hash(data: &Array1<f32>) -> u64 {
+    let bytes = bincode::serialize(data).unwrap_or_default();
+    xxhash_rust::xxh3::xxh3_64(&bytes)
+}
+
+/// CRC32 of a record's id+data/target+metadata bytes, written as a trailing
+/// `u32` after every v4 record so corruption is caught at read time instead
+/// of surfacing as a confusing deserialization failure further downstream.
+fn record_checksum(body: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(body);
+    hasher.finalize()
+}
+
+/// What one on-disk record decodes to. `Reference`/`PayloadOnly` only exist
+/// from `KWI_VERSION` 3 onward (content-hash dedup); a v2 file read under v3
+/// never produces them.
+enum StoredRecord {
+    Live(Vector),
+    Tombstoned,
+    /// `id` has no data of its own; resolve its `data` via `target`'s record.
+    Reference { id: Uuid, target: Uuid, metadata: Option<serde_json::Value> },
+    /// A formerly-live record kept physically around only because a
+    /// `Reference` still points at it; not directly addressable under its
+    /// own ID anymore.
+    PayloadOnly(Vector),
+}
+
+/// Reads one record in the layout used by a historical `KWI_VERSION`.
+/// Returns `Ok(None)` for a tombstoned record so `LocalStorage::upgrade()`
+/// can drop it instead of carrying it forward.
+type LegacyReader = fn(&mut File) -> Result<Option<Vector>>;
+
+/// Registry of historical on-disk layouts `upgrade()` knows how to migrate,
+/// for versions whose records can be read one at a time independent of any
+/// other record. Add an entry here whenever `KWI_VERSION` is bumped for a
+/// record format change, pairing the old version with a reader for its old
+/// layout. Version 3 is *not* in this registry: its `Reference` records
+/// point at another record's data, so migrating it needs a full scan that
+/// can resolve those cross-record pointers; `upgrade()` handles it directly
+/// via `collect_all_records`/`resolve_records` instead of a `LegacyReader`.
+fn legacy_reader_for_version(version: u32) -> Option<LegacyReader> {
+    match version {
+        1 => Some(read_vector_record_v1),
+        2 => Some(read_vector_record_v2),
+        _ => None,
+    }
+}
+
+/// Pre-tombstone v1 record layout: no tombstone flag, so every record read
+/// back is implicitly live.
+fn read_vector_record_v1(file: &mut File) -> Result<Option<Vector>> {
+    let _id_len = match file.read_u32::<LittleEndian>() {
+        Ok(len) => len,
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                return Err(crate::VectorDBError::PersistenceError("End of file reached".to_string()));
+            }
+            return Err(crate::VectorDBError::PersistenceError(format!("Failed to read ID length: {}", e)));
+        }
+    };
+
+    let mut id_bytes = [0u8; 36];
+    match file.read_exact(&mut id_bytes) {
+        Ok(_) => {}
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                return Err(crate::VectorDBError::PersistenceError("End of file reached".to_string()));
+            }
+            return Err(crate::VectorDBError::PersistenceError(format!("Failed to read vector ID: {}", e)));
+        }
+    }
+
+    let id_str = std::str::from_utf8(&id_bytes)
+        .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse vector ID: {}", e)))?
+        .trim_matches('\0');
+    let id = Uuid::parse_str(id_str)
+        .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse UUID: {}", e)))?;
+
+    let data_len = file.read_u32::<LittleEndian>()
+        .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read data length: {}", e)))?;
+    let mut data_bytes = vec![0u8; data_len as usize];
+    file.read_exact(&mut data_bytes)
+        .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read vector data: {}", e)))?;
+    let data: Array1<f32> = bincode::deserialize(&data_bytes)
+        .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize vector data: {}", e)))?;
+
+    let metadata_len = file.read_u32::<LittleEndian>()
+        .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read metadata length: {}", e)))?;
+    let metadata = if metadata_len > 0 {
+        let mut metadata_bytes = vec![0u8; metadata_len as usize];
+        file.read_exact(&mut metadata_bytes)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read metadata: {}", e)))?;
+        let metadata_str = std::str::from_utf8(&metadata_bytes)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse metadata string: {}", e)))?;
+        Some(serde_json::from_str(metadata_str)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize metadata: {}", e)))?)
+    } else {
+        None
+    };
+
+    Ok(Some(Vector { id, data, metadata }))
+}
+
+/// Pre-dedup v2 record layout: identical bytes to v3's live/tombstoned
+/// records (kind byte only ever 0 or 1), so this just reuses that parsing
+/// and drops tombstoned records instead of carrying them forward.
+fn read_vector_record_v2(file: &mut File) -> Result<Option<Vector>> {
+    match read_vector_record_v1_style_with_flag(file)? {
+        None => Ok(None),
+        Some((RECORD_TOMBSTONED, _)) => Ok(None),
+        Some((_, vector)) => Ok(Some(vector)),
+    }
+}
+
+/// Used by `read_vector_record_v2`: reads the flag byte, then the
+/// id+data+metadata record body. EOF before the flag byte is reported the
+/// same way `read_vector_record_v1` reports it ("End of file reached").
+fn read_vector_record_v1_style_with_flag(file: &mut File) -> Result<Option<(u8, Vector)>> {
+    let flag = match file.read_u8() {
+        Ok(flag) => flag,
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                return Err(crate::VectorDBError::PersistenceError("End of file reached".to_string()));
+            }
+            return Err(crate::VectorDBError::PersistenceError(format!("Failed to read record flag: {}", e)));
+        }
+    };
+
+    match read_vector_record_v1(file)? {
+        Some(vector) => Ok(Some((flag, vector))),
+        None => Ok(None),
+    }
+}
+
+/// Stats returned by [`LocalStorage::dedup_stats`] describing content-hash
+/// deduplication savings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DedupStats {
+    pub distinct_payloads: usize,
+    pub duplicate_references: usize,
+    pub bytes_saved: u64,
+}
+
+/// Report returned by [`LocalStorage::verify`]/[`LocalStorage::repair`]: how
+/// many records were scanned, and the byte offset of the first one (if any)
+/// that failed to parse or checksum correctly.
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    pub checked: usize,
+    pub corrupt_offsets: Vec<u64>,
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.corrupt_offsets.is_empty()
+    }
+}
+
 pub struct LocalStorage {
     base_path: PathBuf,
     storage_dir: PathBuf,
     vectors_file: PathBuf,
     metadata_file: PathBuf,
+    offsets_file: PathBuf,
+    dedup_file: PathBuf,
+    compact_threshold: f64,
+    /// Maps every live, reference, or payload-only ID to its byte offset in
+    /// `vectors_file`.
+    offset_index: HashMap<Uuid, u64>,
+    /// Maps a content hash (see `content_hash`) to the canonical ID holding
+    /// that payload's actual data on disk.
+    dedup_index: HashMap<u64, Uuid>,
+    /// Live referrer count per canonical ID: 1 for the canonical record
+    /// itself, plus 1 per reference record pointing at it. A canonical
+    /// record is only physically tombstoned once this reaches zero.
+    ref_counts: HashMap<Uuid, u32>,
+    /// Count of `PayloadOnly` records: present in `offset_index` (needed to
+    /// resolve references) but not in `get_vector_count()` (not directly
+    /// addressable). Lets `load_offset_index` validate its sidecar's length
+    /// exactly, the same way it could before dedup existed.
+    hidden_payload_count: u64,
+    /// Configured by `new_with_embedder`; powers `add_document`/`query_text`.
+    embedder: Option<Box<dyn Embedder>>,
+}
+
+impl std::fmt::Debug for LocalStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalStorage")
+            .field("base_path", &self.base_path)
+            .field("storage_dir", &self.storage_dir)
+            .field("vectors_file", &self.vectors_file)
+            .field("metadata_file", &self.metadata_file)
+            .field("offsets_file", &self.offsets_file)
+            .field("dedup_file", &self.dedup_file)
+            .field("compact_threshold", &self.compact_threshold)
+            .field("offset_index", &self.offset_index)
+            .field("dedup_index", &self.dedup_index)
+            .field("ref_counts", &self.ref_counts)
+            .field("hidden_payload_count", &self.hidden_payload_count)
+            .field("embedder", &self.embedder.as_ref().map(|_| "Embedder(..)"))
+            .finish()
+    }
 }
 
 impl LocalStorage {
     pub fn new<P: AsRef<Path>>(base_path: P) -> Result<Self> {
+        Self::new_with_compact_threshold(base_path, DEFAULT_COMPACT_THRESHOLD)
+    }
+
+    /// Like [`LocalStorage::new`], but with a configurable tombstoned-to-total
+    /// record ratio at which `delete_vector` triggers an automatic `compact()`.
+    pub fn new_with_compact_threshold<P: AsRef<Path>>(base_path: P, compact_threshold: f64) -> Result<Self> {
         let base_path = base_path.as_ref().to_path_buf();
         let storage_dir = base_path.join(STORAGE_DIR);
         let vectors_file = storage_dir.join("vectors.kwi");
         let metadata_file = storage_dir.join("metadata.json");
+        let offsets_file = storage_dir.join("offsets.idx");
+        let dedup_file = storage_dir.join("dedup.idx");
 
         // Create storage directory if it doesn't exist
         fs::create_dir_all(&storage_dir)
@@ -40,19 +276,148 @@ impl LocalStorage {
                 .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write .gitignore: {}", e)))?;
         }
 
-        let storage = Self {
+        let mut storage = Self {
             base_path,
             storage_dir,
             vectors_file,
             metadata_file,
+            offsets_file,
+            dedup_file,
+            compact_threshold,
+            offset_index: HashMap::new(),
+            dedup_index: HashMap::new(),
+            ref_counts: HashMap::new(),
+            hidden_payload_count: 0,
+            embedder: None,
         };
 
         // Initialize storage files
         storage.init_storage()?;
 
+        // Refuse files from a newer build, and transparently migrate older
+        // (but known) formats before anything else touches the file.
+        storage.check_version_and_migrate()?;
+
+        // Dedup index first: `load_offset_index` needs `hidden_payload_count`
+        // from it to validate the offsets sidecar's length.
+        storage.load_dedup_index()?;
+        storage.load_offset_index()?;
+
         Ok(storage)
     }
 
+    /// Like [`LocalStorage::new`], but configured with an [`Embedder`] so
+    /// `add_document`/`query_text` can be used to store and search text
+    /// directly instead of pre-computed vectors.
+    pub fn new_with_embedder<P: AsRef<Path>>(base_path: P, embedder: Box<dyn Embedder>) -> Result<Self> {
+        let mut storage = Self::new(base_path)?;
+        storage.embedder = Some(embedder);
+        Ok(storage)
+    }
+
+    fn read_header_version(&self) -> Result<u32> {
+        let mut file = File::open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+        let mut magic = [0u8; 4];
+        file.read_exact(&mut magic)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read magic: {}", e)))?;
+        if &magic != KWI_MAGIC {
+            return Err(crate::VectorDBError::PersistenceError("Not a KWI vectors file (bad magic)".to_string()));
+        }
+
+        file.read_u32::<LittleEndian>()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read version: {}", e)))
+    }
+
+    /// Refuses to open a file written by a newer build, and transparently
+    /// runs [`LocalStorage::upgrade`] on a file written by an older (but
+    /// still recognized) build.
+    fn check_version_and_migrate(&mut self) -> Result<()> {
+        let version = self.read_header_version()?;
+
+        if version == KWI_VERSION {
+            return Ok(());
+        }
+
+        if version > KWI_VERSION {
+            return Err(crate::VectorDBError::PersistenceError(format!(
+                "vectors.kwi is format version {} but this build only supports up to version {}; use a newer build to open it",
+                version, KWI_VERSION
+            )));
+        }
+
+        self.upgrade()
+    }
+
+    /// Migrates `vectors_file` from whatever version it is currently on to
+    /// `KWI_VERSION`, reading every record with the matching legacy reader
+    /// and rewriting the file with the current layout via the same
+    /// temp-file-then-rename swap `compact()` uses. No-op if the file is
+    /// already current.
+    pub fn upgrade(&mut self) -> Result<()> {
+        let version = self.read_header_version()?;
+
+        if version == KWI_VERSION {
+            return Ok(());
+        }
+
+        if version > KWI_VERSION {
+            return Err(crate::VectorDBError::PersistenceError(format!(
+                "vectors.kwi is format version {} but this build only supports up to version {}; use a newer build to open it",
+                version, KWI_VERSION
+            )));
+        }
+
+        let vectors = if version == 3 {
+            // v3 records may be `Reference`s pointing at another record's
+            // data, so they can't be read one at a time independent of the
+            // rest of the file the way v1/v2 can. Scan the whole (pre-
+            // checksum) file and resolve every reference, the same way
+            // `get_all_vectors`/`compact` do for the current format.
+            let records = self.collect_all_records(false)?;
+            Self::resolve_records(records)
+        } else {
+            let reader = legacy_reader_for_version(version).ok_or_else(|| {
+                crate::VectorDBError::PersistenceError(format!("No migration registered for KWI version {}", version))
+            })?;
+
+            let mut file = File::open(&self.vectors_file)
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+            let file_size = file.metadata()?.len();
+            let mut vectors = Vec::new();
+            if file_size > HEADER_SIZE {
+                file.seek(SeekFrom::Start(HEADER_SIZE))
+                    .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek past header: {}", e)))?;
+
+                loop {
+                    match reader(&mut file) {
+                        Ok(Some(vector)) => vectors.push(vector),
+                        Ok(None) => continue, // tombstoned in the old format; drop it
+                        Err(e) => {
+                            if e.to_string().contains("End of file reached") {
+                                break;
+                            } else {
+                                return Err(e);
+                            }
+                        }
+                    }
+                }
+            }
+            vectors
+        };
+
+        self.recreate_vectors_file(&vectors)?;
+        self.rebuild_offset_index()?;
+        self.persist_offset_index()?;
+        self.rebuild_dedup_index()?;
+        self.persist_dedup_index()?;
+        self.update_metadata()?;
+
+        Ok(())
+    }
+
     fn init_storage(&self) -> Result<()> {
         // Initialize vectors file if it doesn't exist
         if !self.vectors_file.exists() {
@@ -75,7 +440,7 @@ impl LocalStorage {
         file.write_all(KWI_MAGIC)?;
         file.write_u32::<LittleEndian>(KWI_VERSION)?;
         file.write_u64::<LittleEndian>(0)?; // Vector count
-        file.write_u32::<LittleEndian>(0)?; // Reserved
+        file.write_u32::<LittleEndian>(0)?; // Deleted count (tombstoned records)
 
         Ok(())
     }
@@ -100,6 +465,9 @@ impl LocalStorage {
         Ok(())
     }
 
+    /// Appends `vector`. If its payload's content hash matches a payload
+    /// already on disk, no new `Array1<f32>` is written at all: a small
+    /// reference record is appended instead, pointing at the existing data.
     pub fn add_vector(&mut self, vector: &Vector) -> Result<()> {
         let mut file = OpenOptions::new()
             .read(true)
@@ -107,15 +475,27 @@ impl LocalStorage {
             .open(&self.vectors_file)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
 
-        // Seek to end of file
-        file.seek(SeekFrom::End(0))
+        // Seek to end of file, remembering the offset the new record will land at.
+        let offset = file.seek(SeekFrom::End(0))
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to end: {}", e)))?;
 
-        // Write vector data
-        self.write_vector_to_file(&mut file, vector)?;
+        let hash = content_hash(&vector.data);
+        if let Some(&canonical) = self.dedup_index.get(&hash) {
+            self.write_reference_record(&mut file, vector.id, canonical, &vector.metadata)?;
+            *self.ref_counts.entry(canonical).or_insert(1) += 1;
+        } else {
+            self.write_vector_to_file(&mut file, vector)?;
+            self.dedup_index.insert(hash, vector.id);
+            self.ref_counts.insert(vector.id, 1);
+        }
 
         // Update header with new count
         self.update_vector_count(&mut file)?;
+        drop(file);
+
+        self.offset_index.insert(vector.id, offset);
+        self.persist_offset_index()?;
+        self.persist_dedup_index()?;
 
         // Update metadata
         self.update_metadata()?;
@@ -123,43 +503,100 @@ impl LocalStorage {
         Ok(())
     }
 
+    /// Writes a reference record: `ref_id` carries no data of its own, only
+    /// a pointer to `target`'s record (already on disk) and its own
+    /// metadata, which may differ from the target's. A trailing CRC32
+    /// checksum over the body (everything after the flag byte) lets a
+    /// truncated or corrupted reference be caught on read.
+    fn write_reference_record(&self, file: &mut File, ref_id: Uuid, target: Uuid, metadata: &Option<serde_json::Value>) -> Result<()> {
+        file.write_u8(RECORD_REFERENCE)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write record flag: {}", e)))?;
+
+        let mut body = Vec::new();
+
+        let id_str = ref_id.to_string();
+        body.write_u32::<LittleEndian>(id_str.len() as u32)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write ID length: {}", e)))?;
+        let mut id_bytes = [0u8; 36];
+        id_str.as_bytes().iter().enumerate().take(36).for_each(|(i, &byte)| id_bytes[i] = byte);
+        body.write_all(&id_bytes)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write vector ID: {}", e)))?;
+
+        let target_str = target.to_string();
+        let mut target_bytes = [0u8; 36];
+        target_str.as_bytes().iter().enumerate().take(36).for_each(|(i, &byte)| target_bytes[i] = byte);
+        body.write_all(&target_bytes)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write reference target: {}", e)))?;
+
+        Self::encode_metadata_field(&mut body, metadata)?;
+
+        file.write_all(&body)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write reference record: {}", e)))?;
+        file.write_u32::<LittleEndian>(record_checksum(&body))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write record checksum: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Record flag: 0 = live, 1 = tombstoned, 2 = reference, 3 = payload-only.
+    /// Freshly-written full records are always live; later calls flip this
+    /// byte in place (`delete_vector`) or rewrite it entirely (`compact`). A
+    /// trailing CRC32 checksum over the body (everything after the flag
+    /// byte) lets a truncated or corrupted record be caught on read.
     fn write_vector_to_file(&self, file: &mut File, vector: &Vector) -> Result<()> {
-        // Write vector ID length and string
+        file.write_u8(RECORD_LIVE)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write record flag: {}", e)))?;
+
+        let body = Self::encode_vector_body(vector)?;
+        file.write_all(&body)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write vector record: {}", e)))?;
+        file.write_u32::<LittleEndian>(record_checksum(&body))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write record checksum: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Builds the id+data+metadata byte sequence written after a live/
+    /// payload-only record's flag byte; also the exact span covered by its
+    /// trailing CRC32 checksum.
+    fn encode_vector_body(vector: &Vector) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+
         let id_str = vector.id.to_string();
-        file.write_u32::<LittleEndian>(id_str.len() as u32)
+        body.write_u32::<LittleEndian>(id_str.len() as u32)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write ID length: {}", e)))?;
-        
         let mut id_bytes = [0u8; 36];
         id_str.as_bytes().iter().enumerate().take(36).for_each(|(i, &byte)| id_bytes[i] = byte);
-        file.write_all(&id_bytes)
+        body.write_all(&id_bytes)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write vector ID: {}", e)))?;
 
-        // Write vector data
         let data_bytes = bincode::serialize(&vector.data)
             .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize vector data: {}", e)))?;
-        
-        file.write_u32::<LittleEndian>(data_bytes.len() as u32)
+        body.write_u32::<LittleEndian>(data_bytes.len() as u32)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write data length: {}", e)))?;
-        
-        file.write_all(&data_bytes)
+        body.write_all(&data_bytes)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write vector data: {}", e)))?;
 
-        // Write metadata if present
-        if let Some(metadata) = &vector.metadata {
+        Self::encode_metadata_field(&mut body, &vector.metadata)?;
+
+        Ok(body)
+    }
+
+    /// Appends a length-prefixed metadata field to `body`, the same layout
+    /// used by every record kind.
+    fn encode_metadata_field(body: &mut Vec<u8>, metadata: &Option<serde_json::Value>) -> Result<()> {
+        if let Some(metadata) = metadata {
             let metadata_bytes = serde_json::to_string(metadata)
                 .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize metadata: {}", e)))?
                 .into_bytes();
-            
-            file.write_u32::<LittleEndian>(metadata_bytes.len() as u32)
+            body.write_u32::<LittleEndian>(metadata_bytes.len() as u32)
                 .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write metadata length: {}", e)))?;
-            
-            file.write_all(&metadata_bytes)
+            body.write_all(&metadata_bytes)
                 .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write metadata: {}", e)))?;
         } else {
-            file.write_u32::<LittleEndian>(0)
+            body.write_u32::<LittleEndian>(0)
                 .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write zero metadata length: {}", e)))?;
         }
-
         Ok(())
     }
 
@@ -207,75 +644,225 @@ impl LocalStorage {
         Ok(())
     }
 
+    /// O(1): seeks straight to `id`'s record via `offset_index` instead of
+    /// scanning the file. Transparently resolves content-deduplicated
+    /// references to their shared payload.
     pub fn get_vector(&self, id: &Uuid) -> Result<Option<Vector>> {
+        let offset = match self.offset_index.get(id) {
+            Some(&offset) => offset,
+            None => return Ok(None),
+        };
+
+        let mut file = File::open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to record: {}", e)))?;
+
+        match self.read_vector_from_file(&mut file)? {
+            StoredRecord::Live(vector) => Ok(Some(vector)),
+            StoredRecord::Reference { id, target, metadata } => {
+                let data = self.resolve_reference_data(&target)?;
+                Ok(Some(Vector { id, data, metadata }))
+            }
+            StoredRecord::Tombstoned | StoredRecord::PayloadOnly(_) => Ok(None),
+        }
+    }
+
+    /// Every live vector in the store, with content-deduplicated references
+    /// resolved to their shared payload. Correct regardless of whether a
+    /// reference's canonical record appears before or after it on disk.
+    pub fn get_all_vectors(&self) -> Result<Vec<Vector>> {
+        let records = self.collect_all_records(true)?;
+        Ok(Self::resolve_records(records))
+    }
+
+    /// Scans the whole file from just past the header, decoding every
+    /// record. Any non-EOF error (a corrupt checksum, a truncated field)
+    /// propagates rather than being swallowed; use `verify`/`repair` when a
+    /// lenient, offset-reporting scan is what's wanted instead.
+    fn collect_all_records(&self, verify_checksum: bool) -> Result<Vec<StoredRecord>> {
         let mut file = File::open(&self.vectors_file)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
 
-        // Check if file is empty (just header)
         let file_size = file.metadata()?.len();
-        if file_size <= 16 {
-            return Ok(None); // Empty file
+        if file_size <= HEADER_SIZE {
+            return Ok(Vec::new());
         }
 
-        // Skip header
-        file.seek(SeekFrom::Start(16))
+        file.seek(SeekFrom::Start(HEADER_SIZE))
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek past header: {}", e)))?;
 
-        // Read vectors until we find the one we're looking for
+        let mut records = Vec::new();
         loop {
-            match self.read_vector_from_file(&mut file) {
-                Ok(vector) => {
-                    if vector.id == *id {
-                        return Ok(Some(vector));
-                    }
-                }
+            match self.read_stored_record(&mut file, verify_checksum) {
+                Ok(record) => records.push(record),
                 Err(e) => {
                     if e.to_string().contains("End of file reached") {
-                        break; // Normal end of file
+                        break;
                     } else {
-                        return Err(e); // Real error
+                        return Err(e);
                     }
                 }
             }
         }
 
-        Ok(None)
+        Ok(records)
     }
 
-    pub fn get_all_vectors(&self) -> Result<Vec<Vector>> {
+    /// Flattens a full set of decoded records into plain vectors: `Live`
+    /// records pass through as-is, `Reference` records resolve their data
+    /// against whichever `Live`/`PayloadOnly` record shares their target ID
+    /// (silently dropped if dangling), and `Tombstoned`/`PayloadOnly`
+    /// records are excluded.
+    fn resolve_records(records: Vec<StoredRecord>) -> Vec<Vector> {
+        let mut payloads: HashMap<Uuid, Array1<f32>> = HashMap::new();
+        for record in &records {
+            match record {
+                StoredRecord::Live(v) | StoredRecord::PayloadOnly(v) => {
+                    payloads.insert(v.id, v.data.clone());
+                }
+                _ => {}
+            }
+        }
+
+        let mut vectors = Vec::new();
+        for record in records {
+            match record {
+                StoredRecord::Live(v) => vectors.push(v),
+                StoredRecord::Reference { id, target, metadata } => {
+                    if let Some(data) = payloads.get(&target) {
+                        vectors.push(Vector { id, data: data.clone(), metadata });
+                    }
+                }
+                StoredRecord::Tombstoned | StoredRecord::PayloadOnly(_) => {}
+            }
+        }
+
+        vectors
+    }
+
+    /// Embeds `text` with the configured [`Embedder`], stores the result
+    /// with `text` folded into its metadata under `"text"`, and returns the
+    /// new document's ID. Errors if the embedder's output dimension doesn't
+    /// match the dimension of vectors already stored.
+    pub fn add_document(&mut self, text: &str, metadata: Option<serde_json::Value>) -> Result<Uuid> {
+        let embedding = self.embed_text(text)?;
+
+        if let Some(existing_dim) = self.stored_dimension()? {
+            if embedding.len() != existing_dim {
+                return Err(crate::VectorDBError::PersistenceError(format!(
+                    "Embedder produced a {}-dimensional vector but this store already holds {}-dimensional vectors",
+                    embedding.len(),
+                    existing_dim
+                )));
+            }
+        }
+
+        let mut doc_metadata = metadata.unwrap_or_else(|| serde_json::json!({}));
+        if let serde_json::Value::Object(ref mut map) = doc_metadata {
+            map.insert("text".to_string(), serde_json::Value::String(text.to_string()));
+        }
+
+        let vector = Vector::with_metadata(embedding, doc_metadata);
+        let id = vector.id;
+        self.add_vector(&vector)?;
+        Ok(id)
+    }
+
+    /// Embeds `text` with the configured [`Embedder`] and returns the `k`
+    /// most similar stored documents, ranked by cosine similarity
+    /// (highest first).
+    pub fn query_text(&self, text: &str, k: usize) -> Result<Vec<(Vector, f32)>> {
+        let query_embedding = self.embed_text(text)?;
+
+        let mut scored: Vec<(Vector, f32)> = self
+            .get_all_vectors()?
+            .into_iter()
+            .map(|vector| {
+                let score = crate::utils::cosine_similarity(&query_embedding, &vector.data);
+                (vector, score)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+
+        Ok(scored)
+    }
+
+    fn embed_text(&self, text: &str) -> Result<Array1<f32>> {
+        let embedder = self.embedder.as_ref().ok_or_else(|| {
+            crate::VectorDBError::PersistenceError("No embedder configured; use LocalStorage::new_with_embedder".to_string())
+        })?;
+
+        embedder.embed(text)
+    }
+
+    /// Dimension of the first live vector on disk, or `None` if the store is empty.
+    fn stored_dimension(&self) -> Result<Option<usize>> {
         let mut file = File::open(&self.vectors_file)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
 
-        // Check if file is empty (just header)
         let file_size = file.metadata()?.len();
-        if file_size <= 16 {
-            return Ok(Vec::new()); // Empty file
+        if file_size <= HEADER_SIZE {
+            return Ok(None);
         }
 
-        // Skip header
-        file.seek(SeekFrom::Start(16))
+        file.seek(SeekFrom::Start(HEADER_SIZE))
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek past header: {}", e)))?;
 
-        let mut vectors = Vec::new();
-        
         loop {
             match self.read_vector_from_file(&mut file) {
-                Ok(vector) => vectors.push(vector),
+                Ok(StoredRecord::Live(vector)) => return Ok(Some(vector.dimension())),
+                Ok(StoredRecord::Reference { target, .. }) => {
+                    return Ok(Some(self.resolve_reference_data(&target)?.len()));
+                }
+                Ok(StoredRecord::Tombstoned) | Ok(StoredRecord::PayloadOnly(_)) => continue,
                 Err(e) => {
                     if e.to_string().contains("End of file reached") {
-                        break; // Normal end of file
+                        return Ok(None);
                     } else {
-                        return Err(e); // Real error
+                        return Err(e);
                     }
                 }
             }
         }
+    }
 
-        Ok(vectors)
+    /// Reads one record starting at the current file position and decodes
+    /// it according to its record-kind flag. Thin wrapper over
+    /// `read_stored_record` that always verifies the trailing checksum;
+    /// migrating a pre-checksum (v3) file reads with verification off
+    /// instead, via `collect_all_records(false)`.
+    fn read_vector_from_file(&self, file: &mut File) -> Result<StoredRecord> {
+        self.read_stored_record(file, true)
     }
 
-    fn read_vector_from_file(&self, file: &mut File) -> Result<Vector> {
-        // Read vector ID length and string
+    /// Reads one record starting at the current file position and decodes
+    /// it according to its record-kind flag. Every kind is fully parsed
+    /// (rather than skipped) so the cursor ends up in the right place for
+    /// the next call. When `verify_checksum` is set, the trailing CRC32
+    /// written by `write_vector_to_file`/`write_reference_record` is read
+    /// and compared, returning `VectorDBError::CorruptionError` naming the
+    /// record's offset on a mismatch.
+    fn read_stored_record(&self, file: &mut File, verify_checksum: bool) -> Result<StoredRecord> {
+        let record_start = file.stream_position()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read file position: {}", e)))?;
+
+        let flag = match file.read_u8() {
+            Ok(flag) => flag,
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    return Err(crate::VectorDBError::PersistenceError("End of file reached".to_string()));
+                }
+                return Err(crate::VectorDBError::PersistenceError(format!("Failed to read record flag: {}", e)));
+            }
+        };
+
+        // Read vector ID length (the ID itself is always stored in a fixed
+        // 36-byte slot, so the length is only needed to detect EOF here and
+        // to reconstruct the body bytes the checksum covers).
         let id_len = match file.read_u32::<LittleEndian>() {
             Ok(len) => len,
             Err(e) => {
@@ -285,9 +872,7 @@ impl LocalStorage {
                 return Err(crate::VectorDBError::PersistenceError(format!("Failed to read ID length: {}", e)));
             }
         };
-        
-        println!("DEBUG: Read id_len: {}", id_len);
-        
+
         let mut id_bytes = [0u8; 36];
         match file.read_exact(&mut id_bytes) {
             Ok(_) => {},
@@ -298,17 +883,40 @@ impl LocalStorage {
                 return Err(crate::VectorDBError::PersistenceError(format!("Failed to read vector ID: {}", e)));
             }
         }
-        
+
         // Convert bytes to string, trimming null bytes
         let id_str = std::str::from_utf8(&id_bytes)
             .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse vector ID: {}", e)))?
             .trim_matches('\0');
-        
-        println!("DEBUG: id_len: {}, id_str: '{}'", id_len, id_str);
-        
+
         let id = Uuid::parse_str(id_str)
             .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse UUID: {}", e)))?;
 
+        if flag == RECORD_REFERENCE {
+            let mut target_bytes = [0u8; 36];
+            file.read_exact(&mut target_bytes)
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read reference target: {}", e)))?;
+            let target_str = std::str::from_utf8(&target_bytes)
+                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse reference target: {}", e)))?
+                .trim_matches('\0');
+            let target = Uuid::parse_str(target_str)
+                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse reference UUID: {}", e)))?;
+
+            let (metadata_len, metadata_bytes, metadata) = self.read_metadata_field_raw(file)?;
+
+            if verify_checksum {
+                let mut body = Vec::with_capacity(4 + 36 + 36 + 4 + metadata_bytes.len());
+                body.extend_from_slice(&id_len.to_le_bytes());
+                body.extend_from_slice(&id_bytes);
+                body.extend_from_slice(&target_bytes);
+                body.extend_from_slice(&metadata_len.to_le_bytes());
+                body.extend_from_slice(&metadata_bytes);
+                self.verify_record_checksum(file, &body, record_start)?;
+            }
+
+            return Ok(StoredRecord::Reference { id, target, metadata });
+        }
+
         // Read data length
         let data_len = file.read_u32::<LittleEndian>()
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read data length: {}", e)))?;
@@ -317,63 +925,541 @@ impl LocalStorage {
         let mut data_bytes = vec![0u8; data_len as usize];
         file.read_exact(&mut data_bytes)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read vector data: {}", e)))?;
-        
+
         let data: Array1<f32> = bincode::deserialize(&data_bytes)
             .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize vector data: {}", e)))?;
 
-        // Read metadata length
+        let (metadata_len, metadata_bytes, metadata) = self.read_metadata_field_raw(file)?;
+
+        if verify_checksum {
+            let mut body = Vec::with_capacity(4 + 36 + 4 + data_bytes.len() + 4 + metadata_bytes.len());
+            body.extend_from_slice(&id_len.to_le_bytes());
+            body.extend_from_slice(&id_bytes);
+            body.extend_from_slice(&data_len.to_le_bytes());
+            body.extend_from_slice(&data_bytes);
+            body.extend_from_slice(&metadata_len.to_le_bytes());
+            body.extend_from_slice(&metadata_bytes);
+            self.verify_record_checksum(file, &body, record_start)?;
+        }
+
+        let vector = Vector { id, data, metadata };
+
+        Ok(match flag {
+            RECORD_TOMBSTONED => StoredRecord::Tombstoned,
+            RECORD_PAYLOAD_ONLY => StoredRecord::PayloadOnly(vector),
+            _ => StoredRecord::Live(vector),
+        })
+    }
+
+    /// Reads a length-prefixed metadata field, returning the raw length and
+    /// bytes alongside the parsed value so callers verifying a checksum can
+    /// reconstruct the exact body it covers.
+    fn read_metadata_field_raw(&self, file: &mut File) -> Result<(u32, Vec<u8>, Option<serde_json::Value>)> {
         let metadata_len = file.read_u32::<LittleEndian>()
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read metadata length: {}", e)))?;
 
-        // Read metadata if present
-        let metadata = if metadata_len > 0 {
-            let mut metadata_bytes = vec![0u8; metadata_len as usize];
-            file.read_exact(&mut metadata_bytes)
-                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read metadata: {}", e)))?;
-            
-            let metadata_str = std::str::from_utf8(&metadata_bytes)
-                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse metadata string: {}", e)))?;
-            
-            Some(serde_json::from_str(metadata_str)
-                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize metadata: {}", e)))?)
-        } else {
-            None
-        };
+        if metadata_len == 0 {
+            return Ok((0, Vec::new(), None));
+        }
 
-        Ok(Vector {
-            id,
-            data,
-            metadata,
-        })
+        let mut metadata_bytes = vec![0u8; metadata_len as usize];
+        file.read_exact(&mut metadata_bytes)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read metadata: {}", e)))?;
+
+        let metadata_str = std::str::from_utf8(&metadata_bytes)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to parse metadata string: {}", e)))?;
+
+        let metadata = Some(serde_json::from_str(metadata_str)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize metadata: {}", e)))?);
+
+        Ok((metadata_len, metadata_bytes, metadata))
+    }
+
+    /// Reads the trailing CRC32 written after `body` and compares it against
+    /// one freshly computed over `body`, naming `record_start` in the error
+    /// on a mismatch.
+    fn verify_record_checksum(&self, file: &mut File, body: &[u8], record_start: u64) -> Result<()> {
+        let stored = file.read_u32::<LittleEndian>()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read record checksum: {}", e)))?;
+
+        let computed = record_checksum(body);
+        if stored != computed {
+            return Err(crate::VectorDBError::CorruptionError(format!(
+                "record at offset {} failed checksum verification (expected {:#010x}, got {:#010x})",
+                record_start, stored, computed
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Resolves a reference's data by reading `target`'s record directly,
+    /// regardless of whether it's still directly addressable (`Live`) or
+    /// has been hidden behind other referrers (`PayloadOnly`).
+    fn resolve_reference_data(&self, target: &Uuid) -> Result<Array1<f32>> {
+        let offset = self.offset_index.get(target).copied().ok_or_else(|| {
+            crate::VectorDBError::PersistenceError(format!("Dangling content reference to missing record {}", target))
+        })?;
+
+        let mut file = File::open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to record: {}", e)))?;
+
+        match self.read_vector_from_file(&mut file)? {
+            StoredRecord::Live(v) | StoredRecord::PayloadOnly(v) => Ok(v.data),
+            _ => Err(crate::VectorDBError::PersistenceError(format!(
+                "Content reference target {} is not a payload record",
+                target
+            ))),
+        }
     }
 
+    /// Total live vector count, i.e. records written minus tombstoned records.
     pub fn get_vector_count(&self) -> Result<usize> {
         let mut file = File::open(&self.vectors_file)
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
 
         file.seek(SeekFrom::Start(8))
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to count position: {}", e)))?;
-        
-        let count = file.read_u64::<LittleEndian>()
+
+        let total_count = file.read_u64::<LittleEndian>()
             .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read count: {}", e)))?;
 
-        Ok(count as usize)
+        let deleted_count = file.read_u32::<LittleEndian>()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read deleted count: {}", e)))?;
+
+        Ok((total_count - deleted_count as u64) as usize)
+    }
+
+    /// Number of tombstoned records that have not yet been reclaimed by `compact()`.
+    pub fn deleted_count(&self) -> Result<usize> {
+        let mut file = File::open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+        file.seek(SeekFrom::Start(16))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to deleted count position: {}", e)))?;
+
+        let deleted_count = file.read_u32::<LittleEndian>()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read deleted count: {}", e)))?;
+
+        Ok(deleted_count as usize)
     }
 
+    fn increment_deleted_count(&self, file: &mut File) -> Result<()> {
+        file.seek(SeekFrom::Start(16))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to deleted count position: {}", e)))?;
+
+        let current = file.read_u32::<LittleEndian>()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read deleted count: {}", e)))?;
+
+        file.seek(SeekFrom::Start(16))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek back to deleted count position: {}", e)))?;
+
+        file.write_u32::<LittleEndian>(current + 1)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write deleted count: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Flips a record's flag in place instead of rewriting the whole file.
+    /// `offset_index` gives the record's position directly, so a delete is
+    /// O(1) rather than a linear scan. Reference-counting-aware: deleting a
+    /// content-deduplicated vector's own ID only hides it (`PayloadOnly`)
+    /// while other records still reference its payload; the underlying
+    /// bytes are only tombstoned once the last referrer is gone. Triggers
+    /// `compact()` once the tombstoned-to-total record ratio crosses
+    /// `compact_threshold`.
     pub fn delete_vector(&mut self, id: &Uuid) -> Result<()> {
-        // For simplicity, we'll rebuild the file without the deleted vector
-        let vectors = self.get_all_vectors()?;
-        let filtered_vectors: Vec<_> = vectors.into_iter().filter(|v| v.id != *id).collect();
+        let record_start = match self.offset_index.get(id) {
+            Some(&offset) => offset,
+            None => return Ok(()), // Not present; nothing to delete.
+        };
 
-        // Recreate the file
-        self.recreate_vectors_file(&filtered_vectors)?;
-        
-        // Update metadata
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+        file.seek(SeekFrom::Start(record_start))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to record: {}", e)))?;
+        let record = self.read_vector_from_file(&mut file)?;
+
+        match record {
+            StoredRecord::Tombstoned => return Ok(()), // Already gone.
+
+            StoredRecord::Reference { target, .. } => {
+                Self::write_flag_at(&mut file, record_start, RECORD_TOMBSTONED)?;
+                self.increment_deleted_count(&mut file)?;
+                self.offset_index.remove(id);
+
+                let remaining = self.ref_counts.get_mut(&target).map(|count| {
+                    *count = count.saturating_sub(1);
+                    *count
+                });
+
+                if remaining == Some(0) {
+                    if let Some(&target_offset) = self.offset_index.get(&target) {
+                        Self::write_flag_at(&mut file, target_offset, RECORD_TOMBSTONED)?;
+                    }
+                    self.offset_index.remove(&target);
+                    self.ref_counts.remove(&target);
+                    self.hidden_payload_count = self.hidden_payload_count.saturating_sub(1);
+                    self.dedup_index.retain(|_, canonical| *canonical != target);
+                }
+            }
+
+            StoredRecord::Live(_) | StoredRecord::PayloadOnly(_) => {
+                let referrers = self.ref_counts.get(id).copied().unwrap_or(1);
+
+                if referrers <= 1 {
+                    Self::write_flag_at(&mut file, record_start, RECORD_TOMBSTONED)?;
+                    self.increment_deleted_count(&mut file)?;
+                    self.offset_index.remove(id);
+                    self.ref_counts.remove(id);
+                    self.dedup_index.retain(|_, canonical| canonical != id);
+                } else {
+                    // Other records still reference this payload: hide it
+                    // under its own ID but keep the bytes alive.
+                    Self::write_flag_at(&mut file, record_start, RECORD_PAYLOAD_ONLY)?;
+                    self.increment_deleted_count(&mut file)?;
+                    self.hidden_payload_count += 1;
+                    *self.ref_counts.get_mut(id).unwrap() -= 1;
+                }
+            }
+        }
+        drop(file);
+
+        self.persist_offset_index()?;
+        self.persist_dedup_index()?;
+        self.update_metadata()?;
+        self.maybe_compact()?;
+        Ok(())
+    }
+
+    fn write_flag_at(file: &mut File, offset: u64, flag: u8) -> Result<()> {
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to record: {}", e)))?;
+        file.write_u8(flag)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write record flag: {}", e)))?;
+        Ok(())
+    }
+
+    /// Runs `compact()` if the fraction of tombstoned records has crossed
+    /// `compact_threshold`, so space is reclaimed without paying the cost of
+    /// a rebuild on every single delete.
+    fn maybe_compact(&mut self) -> Result<()> {
+        let mut file = File::open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+        file.seek(SeekFrom::Start(8))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek to count position: {}", e)))?;
+        let total_count = file.read_u64::<LittleEndian>()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read count: {}", e)))?;
+        let deleted_count = file.read_u32::<LittleEndian>()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read deleted count: {}", e)))?;
+        drop(file);
+
+        if total_count == 0 {
+            return Ok(());
+        }
+
+        let ratio = deleted_count as f64 / total_count as f64;
+        if ratio >= self.compact_threshold {
+            self.compact()?;
+        }
+
+        Ok(())
+    }
+
+    /// Rebuilds the vectors file, dropping tombstoned records and resetting
+    /// the deleted-count header slot to zero. `get_all_vectors` resolves
+    /// every content reference to its full data first, so this also
+    /// flattens dedup state: the rebuilt file holds one plain `Live` record
+    /// per distinct ID, and dedup bookkeeping is rebuilt fresh from it
+    /// (still deduplicating future `add_vector` calls against the same
+    /// payloads, just without any `Reference`/`PayloadOnly` records left
+    /// over from before the compaction).
+    pub fn compact(&mut self) -> Result<()> {
+        let vectors = self.get_all_vectors()?;
+        self.recreate_vectors_file(&vectors)?;
+        // Every record moved, so the offset index must be rebuilt from the
+        // freshly-written file rather than patched up in place.
+        self.rebuild_offset_index()?;
+        self.persist_offset_index()?;
+        self.rebuild_dedup_index()?;
+        self.persist_dedup_index()?;
         self.update_metadata()?;
+        Ok(())
+    }
 
+    /// Loads `offset_index` from the `offsets.idx` sidecar, validating its
+    /// entry count against the current live-plus-hidden-payload count.
+    /// Falls back to a full rescan (and rewrites the sidecar) if the file
+    /// is missing, stale, or corrupt. Requires `hidden_payload_count` (from
+    /// `load_dedup_index`) to already be current.
+    fn load_offset_index(&mut self) -> Result<()> {
+        let expected_count = self.get_vector_count()? as u64 + self.hidden_payload_count;
+
+        if let Ok(bytes) = fs::read(&self.offsets_file) {
+            if let Ok(index) = bincode::deserialize::<HashMap<Uuid, u64>>(&bytes) {
+                if index.len() as u64 == expected_count {
+                    self.offset_index = index;
+                    return Ok(());
+                }
+            }
+        }
+
+        self.rebuild_offset_index()?;
+        self.persist_offset_index()?;
         Ok(())
     }
 
+    /// Rebuilds `offset_index` from scratch by scanning `vectors_file`.
+    /// Every directly-addressable or reference-resolvable record (`Live`,
+    /// `Reference`, `PayloadOnly`) gets an entry; only `Tombstoned` records
+    /// are excluded.
+    fn rebuild_offset_index(&mut self) -> Result<()> {
+        self.offset_index.clear();
+
+        let mut file = File::open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+        let file_size = file.metadata()?.len();
+        if file_size <= HEADER_SIZE {
+            return Ok(());
+        }
+
+        file.seek(SeekFrom::Start(HEADER_SIZE))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek past header: {}", e)))?;
+
+        loop {
+            let record_start = file.stream_position()
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read file position: {}", e)))?;
+
+            match self.read_vector_from_file(&mut file) {
+                Ok(StoredRecord::Live(vector)) => {
+                    self.offset_index.insert(vector.id, record_start);
+                }
+                Ok(StoredRecord::PayloadOnly(vector)) => {
+                    self.offset_index.insert(vector.id, record_start);
+                }
+                Ok(StoredRecord::Reference { id, .. }) => {
+                    self.offset_index.insert(id, record_start);
+                }
+                Ok(StoredRecord::Tombstoned) => {}
+                Err(e) => {
+                    if e.to_string().contains("End of file reached") {
+                        break;
+                    } else {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn persist_offset_index(&self) -> Result<()> {
+        let bytes = bincode::serialize(&self.offset_index)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize offset index: {}", e)))?;
+
+        fs::write(&self.offsets_file, bytes)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write offset index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Loads `dedup_index`/`ref_counts`/`hidden_payload_count` from the
+    /// `dedup.idx` sidecar, falling back to a full rescan if it's missing,
+    /// corrupt, or references an ID no longer present on disk.
+    fn load_dedup_index(&mut self) -> Result<()> {
+        if let Ok(bytes) = fs::read(&self.dedup_file) {
+            if let Ok((dedup_index, ref_counts, hidden_payload_count)) =
+                bincode::deserialize::<(HashMap<u64, Uuid>, HashMap<Uuid, u32>, u64)>(&bytes)
+            {
+                self.dedup_index = dedup_index;
+                self.ref_counts = ref_counts;
+                self.hidden_payload_count = hidden_payload_count;
+                return Ok(());
+            }
+        }
+
+        self.rebuild_dedup_index()?;
+        self.persist_dedup_index()?;
+        Ok(())
+    }
+
+    /// Rebuilds `dedup_index`/`ref_counts`/`hidden_payload_count` from
+    /// scratch by scanning `vectors_file`.
+    fn rebuild_dedup_index(&mut self) -> Result<()> {
+        self.dedup_index.clear();
+        self.ref_counts.clear();
+        self.hidden_payload_count = 0;
+
+        let mut file = File::open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+        let file_size = file.metadata()?.len();
+        if file_size <= HEADER_SIZE {
+            return Ok(());
+        }
+
+        file.seek(SeekFrom::Start(HEADER_SIZE))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek past header: {}", e)))?;
+
+        loop {
+            match self.read_vector_from_file(&mut file) {
+                Ok(StoredRecord::Live(vector)) => {
+                    self.dedup_index.insert(content_hash(&vector.data), vector.id);
+                    *self.ref_counts.entry(vector.id).or_insert(0) += 1;
+                }
+                Ok(StoredRecord::PayloadOnly(vector)) => {
+                    self.dedup_index.insert(content_hash(&vector.data), vector.id);
+                    self.hidden_payload_count += 1;
+                }
+                Ok(StoredRecord::Reference { target, .. }) => {
+                    *self.ref_counts.entry(target).or_insert(0) += 1;
+                }
+                Ok(StoredRecord::Tombstoned) => {}
+                Err(e) => {
+                    if e.to_string().contains("End of file reached") {
+                        break;
+                    } else {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn persist_dedup_index(&self) -> Result<()> {
+        let bytes = bincode::serialize(&(&self.dedup_index, &self.ref_counts, self.hidden_payload_count))
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize dedup index: {}", e)))?;
+
+        fs::write(&self.dedup_file, bytes)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to write dedup index: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Reports content-hash deduplication savings: how many distinct
+    /// payloads are stored, how many IDs merely reference one, and roughly
+    /// how many bytes of `Array1<f32>` data were never written as a result.
+    pub fn dedup_stats(&self) -> Result<DedupStats> {
+        let distinct_payloads = self.dedup_index.len();
+        let duplicate_references: usize = self
+            .ref_counts
+            .values()
+            .map(|&count| count.saturating_sub(1) as usize)
+            .sum();
+
+        let bytes_saved: u64 = self
+            .dedup_index
+            .values()
+            .filter_map(|&canonical| {
+                let extra_refs = self.ref_counts.get(&canonical).copied().unwrap_or(1).saturating_sub(1);
+                if extra_refs == 0 {
+                    return None;
+                }
+                let data = self.resolve_reference_data(&canonical).ok()?;
+                let payload_bytes = bincode::serialize(&data).ok()?.len() as u64;
+                Some(payload_bytes * extra_refs as u64)
+            })
+            .sum();
+
+        Ok(DedupStats {
+            distinct_payloads,
+            duplicate_references,
+            bytes_saved,
+        })
+    }
+
+    /// Alias for [`LocalStorage::compact`], for callers used to SQL's VACUUM vocabulary.
+    pub fn vacuum(&mut self) -> Result<()> {
+        self.compact()
+    }
+
+    /// Like `collect_all_records`, but stops at the first record that fails
+    /// to parse or checksum instead of propagating an error, reporting that
+    /// record's offset alongside everything read before it. A clean file
+    /// scans to EOF with no offset reported.
+    fn scan_records_lenient(&self, verify_checksum: bool) -> Result<(Vec<StoredRecord>, Option<u64>)> {
+        let mut file = File::open(&self.vectors_file)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open vectors file: {}", e)))?;
+
+        let file_size = file.metadata()?.len();
+        if file_size <= HEADER_SIZE {
+            return Ok((Vec::new(), None));
+        }
+
+        file.seek(SeekFrom::Start(HEADER_SIZE))
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to seek past header: {}", e)))?;
+
+        let mut records = Vec::new();
+        loop {
+            let record_start = file.stream_position()
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to read file position: {}", e)))?;
+
+            match self.read_stored_record(&mut file, verify_checksum) {
+                Ok(record) => records.push(record),
+                Err(e) => {
+                    if e.to_string().contains("End of file reached") {
+                        return Ok((records, None));
+                    }
+                    return Ok((records, Some(record_start)));
+                }
+            }
+        }
+    }
+
+    /// Scans every record, verifying its trailing checksum, without
+    /// modifying anything. Stops at the first record that fails to parse or
+    /// checksum correctly — a truncated or bit-flipped append leaves
+    /// everything after it unreliable to resync against — and reports that
+    /// record's byte offset. An empty `corrupt_offsets` means the whole
+    /// file verified clean.
+    pub fn verify(&self) -> Result<IntegrityReport> {
+        let (records, corrupt_offset) = self.scan_records_lenient(true)?;
+
+        Ok(IntegrityReport {
+            checked: records.len() + corrupt_offset.map_or(0, |_| 1),
+            corrupt_offsets: corrupt_offset.into_iter().collect(),
+        })
+    }
+
+    /// Runs `verify()` and, if it found a corrupt or truncated record,
+    /// rewrites the file keeping only the well-formed records before it (via
+    /// the same temp-file-then-rename swap `compact()` uses), so a
+    /// partially-written append doesn't render the rest of the collection
+    /// unreadable. No-op beyond the scan if the file already verifies clean.
+    pub fn repair(&mut self) -> Result<IntegrityReport> {
+        let (records, corrupt_offset) = self.scan_records_lenient(true)?;
+
+        let report = IntegrityReport {
+            checked: records.len() + corrupt_offset.map_or(0, |_| 1),
+            corrupt_offsets: corrupt_offset.into_iter().collect(),
+        };
+
+        if report.is_ok() {
+            return Ok(report);
+        }
+
+        let vectors = Self::resolve_records(records);
+        self.recreate_vectors_file(&vectors)?;
+        self.rebuild_offset_index()?;
+        self.persist_offset_index()?;
+        self.rebuild_dedup_index()?;
+        self.persist_dedup_index()?;
+        self.update_metadata()?;
+
+        Ok(report)
+    }
+
     fn recreate_vectors_file(&self, vectors: &[Vector]) -> Result<()> {
         // Create temporary file
         let temp_file = self.storage_dir.join("vectors_temp.kwi");
@@ -384,7 +1470,7 @@ impl LocalStorage {
         file.write_all(KWI_MAGIC)?;
         file.write_u32::<LittleEndian>(KWI_VERSION)?;
         file.write_u64::<LittleEndian>(vectors.len() as u64)?;
-        file.write_u32::<LittleEndian>(0)?; // Reserved
+        file.write_u32::<LittleEndian>(0)?; // Deleted count (tombstoned records)
 
         // Write all vectors
         for vector in vectors {
@@ -401,6 +1487,12 @@ impl LocalStorage {
     pub fn clear(&mut self) -> Result<()> {
         // Recreate empty vectors file
         self.create_vectors_file()?;
+        self.offset_index.clear();
+        self.persist_offset_index()?;
+        self.dedup_index.clear();
+        self.ref_counts.clear();
+        self.hidden_payload_count = 0;
+        self.persist_dedup_index()?;
         self.update_metadata()?;
 
         Ok(())
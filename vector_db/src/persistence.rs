@@ -0,0 +1,73 @@
+use crate::{vector::Vector, Result, VectorDBError};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Stores a collection's vectors as a single pretty-printed JSON array file.
+/// Every write rewrites the whole file, so this trades update throughput for
+/// the simplicity of a portable, single-file, human-inspectable format —
+/// the mode [`crate::collection_manager::CollectionManager`] picks for
+/// "single-file portable" collections.
+pub struct PersistentStorage {
+    file_path: std::path::PathBuf,
+}
+
+impl PersistentStorage {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            file_path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn save_vectors(vectors: &[Vector], path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(vectors)
+            .map_err(|e| VectorDBError::SerializationError(format!("Failed to serialize vectors to JSON: {}", e)))?;
+
+        let mut file = File::create(path)
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to create file for writing: {}", e)))?;
+
+        file.write_all(json.as_bytes())
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to write vectors to file: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub fn load_vectors(path: &Path) -> Result<Vec<Vector>> {
+        let mut file = File::open(path)
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to open file for reading: {}", e)))?;
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .map_err(|e| VectorDBError::PersistenceError(format!("Failed to read file contents: {}", e)))?;
+
+        let vectors: Vec<Vector> = serde_json::from_str(&contents)
+            .map_err(|e| VectorDBError::SerializationError(format!("Failed to deserialize vectors from JSON: {}", e)))?;
+
+        Ok(vectors)
+    }
+
+    pub fn save(&self, vectors: &[Vector]) -> Result<()> {
+        Self::save_vectors(vectors, &self.file_path)
+    }
+
+    pub fn load(&self) -> Result<Vec<Vector>> {
+        if !self.file_path.exists() {
+            return Ok(Vec::new());
+        }
+        Self::load_vectors(&self.file_path)
+    }
+
+    pub fn append_vector(&self, vector: &Vector) -> Result<()> {
+        let mut vectors = self.load()?;
+        vectors.push(vector.clone());
+        self.save(&vectors)
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        if self.file_path.exists() {
+            std::fs::remove_file(&self.file_path)
+                .map_err(|e| VectorDBError::PersistenceError(format!("Failed to remove existing file: {}", e)))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{index::Index, storage::Storage, utils::{cosine_similarity, euclidean_distance}, vector::Vector, Result, VectorDBError};
+use ndarray::Array1;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+/// Default `C` constant for Reciprocal Rank Fusion, as recommended by the
+/// original RRF paper. Dampens the contribution of low ranks so a single
+/// list can't dominate the fused score just by ranking something first.
+pub const DEFAULT_RRF_C: f32 = 60.0;
+
+/// Explains why a result landed where it did, so callers can show "why
+/// this matched" without rerunning distance functions themselves.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreDetail {
+    /// Produced by a pure vector-similarity search: the raw similarity
+    /// numbers behind the index's ranking.
+    Vector { cosine: f32, euclidean: f32 },
+    /// Produced by a metadata/keyword match. `matched_fields` is a
+    /// best-effort list of the top-level metadata keys present on the
+    /// match, since the predicate itself is an opaque `Fn(&Value) -> bool`
+    /// and doesn't report which field it keyed off of.
+    Metadata { matched_fields: Vec<String> },
+    /// Produced by [`QueryEngine::hybrid_search`]: the Reciprocal Rank
+    /// Fusion score and each list's 1-based rank for this id, or `None`
+    /// where the id didn't appear in that list at all.
+    Fused {
+        rrf_score: f32,
+        vector_rank: Option<usize>,
+        metadata_rank: Option<usize>,
+    },
+}
+
+/// Ties a [`Storage`] to an [`Index`] built over the same vectors and
+/// offers search on top of the pair. Holds borrows rather than owning
+/// either side, since both are typically long-lived and shared with other
+/// callers (e.g. whatever inserted the vectors in the first place).
+pub struct QueryEngine<'a, S: Storage, I: Index> {
+    storage: &'a S,
+    index: &'a I,
+}
+
+impl<'a, S: Storage, I: Index> QueryEngine<'a, S, I> {
+    pub fn new(storage: &'a S, index: &'a I) -> Self {
+        Self { storage, index }
+    }
+
+    /// Pure vector similarity search: queries the index for the closest
+    /// `top_k` ids and resolves each back to its stored `Vector`, alongside
+    /// a [`ScoreDetail::Vector`] explaining the match.
+    pub fn search(&self, query: &Vector, top_k: usize) -> Result<Vec<(Vector, f32, ScoreDetail)>> {
+        Ok(self
+            .index
+            .query(&query.data, top_k)
+            .into_iter()
+            .filter_map(|(id, score)| {
+                self.storage.get(&id).map(|v| {
+                    let detail = ScoreDetail::Vector {
+                        cosine: cosine_similarity(&query.data, &v.data),
+                        euclidean: euclidean_distance(&query.data, &v.data),
+                    };
+                    (v, score, detail)
+                })
+            })
+            .collect())
+    }
+
+    /// Like [`Self::search`], but drops any hit whose similarity score is
+    /// below `min_similarity`. The result can legitimately hold fewer than
+    /// `top_k` entries (or none at all) when the collection has no close
+    /// enough matches, instead of padding out with weak ones.
+    pub fn search_with_threshold(
+        &self,
+        query: &Vector,
+        top_k: usize,
+        min_similarity: f32,
+    ) -> Result<Vec<(Vector, f32, ScoreDetail)>> {
+        Ok(self
+            .search(query, top_k)?
+            .into_iter()
+            .filter(|(_, score, _)| *score >= min_similarity)
+            .collect())
+    }
+
+    /// Hybrid search combining vector similarity with a metadata predicate
+    /// via Reciprocal Rank Fusion, using [`DEFAULT_RRF_C`].
+    ///
+    /// `metadata_predicate` is run against each stored vector's metadata
+    /// (vectors with no metadata never match) to build a second ranked
+    /// list alongside the vector-similarity ranking; see
+    /// [`Self::hybrid_search_with_c`] for how the two are fused.
+    pub fn hybrid_search<P>(
+        &self,
+        query: &Vector,
+        metadata_predicate: P,
+        k: usize,
+    ) -> Result<Vec<(Vector, f32, ScoreDetail)>>
+    where
+        P: Fn(&serde_json::Value) -> bool,
+    {
+        self.hybrid_search_with_c(query, metadata_predicate, k, DEFAULT_RRF_C)
+    }
+
+    /// Same as [`Self::hybrid_search`] but with an explicit RRF `c`
+    /// constant instead of [`DEFAULT_RRF_C`].
+    ///
+    /// Builds two ranked id lists — one from the index's vector-similarity
+    /// query, one from vectors matching `metadata_predicate` in storage
+    /// iteration order — then fuses them with
+    /// `fused_score = sum_over_lists 1/(c + rank)`, where `rank` is the
+    /// 1-based position of an id in that list. An id missing from a list
+    /// contributes nothing for that list. The top `k` ids by fused score
+    /// are resolved back to their stored `Vector`s.
+    pub fn hybrid_search_with_c<P>(
+        &self,
+        query: &Vector,
+        metadata_predicate: P,
+        k: usize,
+        c: f32,
+    ) -> Result<Vec<(Vector, f32, ScoreDetail)>>
+    where
+        P: Fn(&serde_json::Value) -> bool,
+    {
+        let vector_ranked: Vec<Uuid> = self
+            .index
+            .query(&query.data, self.storage.count())
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+
+        let metadata_ranked: Vec<Uuid> = self
+            .storage
+            .all_vectors()
+            .into_iter()
+            .filter(|v| {
+                v.metadata
+                    .as_ref()
+                    .map(|m| metadata_predicate(m))
+                    .unwrap_or(false)
+            })
+            .map(|v| v.id)
+            .collect();
+
+        let vector_ranks: HashMap<Uuid, usize> = vector_ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, id)| (*id, rank + 1))
+            .collect();
+        let metadata_ranks: HashMap<Uuid, usize> = metadata_ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, id)| (*id, rank + 1))
+            .collect();
+
+        let mut fused_scores: HashMap<Uuid, f32> = HashMap::new();
+        for (rank, id) in vector_ranked.into_iter().enumerate() {
+            *fused_scores.entry(id).or_insert(0.0) += 1.0 / (c + (rank + 1) as f32);
+        }
+        for (rank, id) in metadata_ranked.into_iter().enumerate() {
+            *fused_scores.entry(id).or_insert(0.0) += 1.0 / (c + (rank + 1) as f32);
+        }
+
+        let mut results: Vec<(Uuid, f32)> = fused_scores.into_iter().collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(k);
+
+        Ok(results
+            .into_iter()
+            .filter_map(|(id, score)| {
+                self.storage.get(&id).map(|v| {
+                    let detail = ScoreDetail::Fused {
+                        rrf_score: score,
+                        vector_rank: vector_ranks.get(&id).copied(),
+                        metadata_rank: metadata_ranks.get(&id).copied(),
+                    };
+                    (v, score, detail)
+                })
+            })
+            .collect())
+    }
+}
+
+/// One request the [`AsyncQueryEngine`] dispatch loop can service.
+enum Command {
+    FindSimilar {
+        query: Array1<f32>,
+        threshold: f32,
+        limit: usize,
+        responder: oneshot::Sender<Vec<(Vector, f32)>>,
+    },
+}
+
+/// Background worker pool around a shared [`Storage`]+[`Index`] pair that
+/// services similarity queries without blocking the caller.
+///
+/// A single dispatch task owns the `Arc<S>`/`Arc<I>` pair and receives
+/// [`Command`]s over an unbounded channel; each command is handed off to
+/// its own spawned task (cloning the `Arc`s), so many reads can run
+/// concurrently against the same index. This engine is read-only — callers
+/// that also need to insert must serialize writes to the underlying
+/// storage/index themselves, the same way [`crate::async_local_storage::AsyncLocalStorage`]
+/// guards its own writes with an internal lock.
+pub struct AsyncQueryEngine {
+    command_tx: mpsc::UnboundedSender<Command>,
+}
+
+impl AsyncQueryEngine {
+    /// Spawns the dispatch loop over `storage` and `index`. Use
+    /// [`Self::find_similar`] to issue queries against it.
+    pub fn new<S, I>(storage: Arc<S>, index: Arc<I>) -> Self
+    where
+        S: Storage + Send + Sync + 'static,
+        I: Index + Send + Sync + 'static,
+    {
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel::<Command>();
+
+        tokio::spawn(async move {
+            while let Some(command) = command_rx.recv().await {
+                match command {
+                    Command::FindSimilar { query, threshold, limit, responder } => {
+                        let storage = Arc::clone(&storage);
+                        let index = Arc::clone(&index);
+                        tokio::spawn(async move {
+                            let results = index
+                                .query(&query, limit)
+                                .into_iter()
+                                .filter(|(_, score)| *score >= threshold)
+                                .filter_map(|(id, score)| storage.get(&id).map(|v| (v, score)))
+                                .collect();
+                            let _ = responder.send(results);
+                        });
+                    }
+                }
+            }
+        });
+
+        Self { command_tx }
+    }
+
+    /// Finds up to `limit` vectors whose similarity to `query` is at least
+    /// `threshold`, without blocking the caller while the index is scanned.
+    /// Runs concurrently with any other in-flight `find_similar` call
+    /// against the same engine.
+    pub async fn find_similar(
+        &self,
+        query: &Vector,
+        threshold: f32,
+        limit: usize,
+    ) -> Result<Vec<(Vector, f32)>> {
+        let (responder, response) = oneshot::channel();
+
+        self.command_tx
+            .send(Command::FindSimilar {
+                query: query.data.clone(),
+                threshold,
+                limit,
+                responder,
+            })
+            .map_err(|_| VectorDBError::IndexError("query worker pool has shut down".to_string()))?;
+
+        response
+            .await
+            .map_err(|_| VectorDBError::IndexError("query worker dropped its response".to_string()))
+    }
+}
@@ -1,75 +1,102 @@
 use crate::{vector::Vector, Result};
-use rusqlite::{Connection, Result as SqliteResult, params, Row};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Result as SqliteResult, params, Row};
 use uuid::Uuid;
 use std::path::Path;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use serde_json::Value;
 
 pub struct SQLiteStorage {
     conn: Connection,
     collection_name: String,
+    read_only: bool,
 }
 
 impl SQLiteStorage {
     pub fn new<P: AsRef<Path>>(db_path: P, collection_name: &str) -> Result<Self> {
         let conn = Connection::open(db_path)
             .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to open SQLite database: {}", e)))?;
-        
+
+        // WAL lets readers and the bulk writer in `insert_vectors` proceed
+        // without blocking each other, and NORMAL synchronous is still
+        // crash-safe under WAL while avoiding an fsync on every transaction.
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to set journal_mode pragma: {}", e)))?;
+        conn.pragma_update(None, "synchronous", "NORMAL")
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to set synchronous pragma: {}", e)))?;
+
         let storage = Self {
             conn,
             collection_name: collection_name.to_string(),
+            read_only: false,
         };
-        
+
         storage.init_tables()?;
         Ok(storage)
     }
 
-    fn init_tables(&self) -> Result<()> {
-        // Create collections table
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS collections (
-                id INTEGER PRIMARY KEY,
-                name TEXT UNIQUE NOT NULL,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to create collections table: {}", e)))?;
+    /// Opens `db_path` with SQLite's read-only open flags, the same
+    /// separation a backup or reporting workload wants from the writer: no
+    /// tables are created or migrated, and every mutating call below is
+    /// rejected up front with a clear `StorageError` instead of failing
+    /// deep inside a SQLite write that can never succeed on this handle.
+    pub fn open_read_only<P: AsRef<Path>>(db_path: P, collection_name: &str) -> Result<Self> {
+        let conn = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to open SQLite database read-only: {}", e)))?;
+
+        Ok(Self {
+            conn,
+            collection_name: collection_name.to_string(),
+            read_only: true,
+        })
+    }
 
-        // Create vectors table
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS vectors (
-                id TEXT PRIMARY KEY,
-                collection_id INTEGER NOT NULL,
-                dimension INTEGER NOT NULL,
-                data BLOB NOT NULL,
-                metadata TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (collection_id) REFERENCES collections (id)
-            )",
-            [],
-        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to create vectors table: {}", e)))?;
+    fn require_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(crate::VectorDBError::StorageError(
+                "storage was opened read-only".to_string(),
+            ));
+        }
+        Ok(())
+    }
 
-        // Create system_info table
-        self.conn.execute(
-            "CREATE TABLE IF NOT EXISTS system_info (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL,
-                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to create system_info table: {}", e)))?;
+    /// Flushes the WAL back into the main database file so the unit of work
+    /// done so far is durable on disk, without requiring the caller to drop
+    /// the connection. Read-only handles have nothing to flush.
+    pub fn commit(&self) -> Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
 
-        // Insert or update collection
-        self.conn.execute(
-            "INSERT OR REPLACE INTO collections (name, updated_at) VALUES (?, CURRENT_TIMESTAMP)",
-            params![self.collection_name],
-        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to insert collection: {}", e)))?;
+        self.conn
+            .execute_batch("PRAGMA wal_checkpoint(PASSIVE)")
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to checkpoint WAL: {}", e)))?;
 
         Ok(())
     }
 
+    /// Commits any outstanding work and explicitly closes the underlying
+    /// connection, so the caller knows the database is durable and released
+    /// rather than relying on an implicit close when the handle is dropped.
+    pub fn close(self) -> Result<()> {
+        self.commit()?;
+        self.conn
+            .close()
+            .map_err(|(_, e)| crate::VectorDBError::StorageError(format!("Failed to close SQLite connection: {}", e)))
+    }
+
+    fn init_tables(&self) -> Result<()> {
+        init_tables(&self.conn, &self.collection_name)
+    }
+
     pub fn insert_vector(&self, vector: &Vector) -> Result<()> {
+        self.require_writable()?;
         let collection_id = self.get_collection_id()?;
         let data = bincode::serialize(&vector.data)
             .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize vector data: {}", e)))?;
@@ -93,9 +120,63 @@ impl SQLiteStorage {
         Ok(())
     }
 
+    /// Inserts `vectors` in a single explicit transaction, reusing one
+    /// prepared statement across every row instead of the autocommit,
+    /// one-statement-per-row path `insert_vector` takes. Rolls back and
+    /// returns the first error if any row fails to insert.
+    pub fn insert_vectors(&self, vectors: &[Vector]) -> Result<()> {
+        self.require_writable()?;
+        if vectors.is_empty() {
+            return Ok(());
+        }
+
+        let collection_id = self.get_collection_id()?;
+
+        self.conn.execute_batch("BEGIN")
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to begin transaction: {}", e)))?;
+
+        let result: Result<()> = (|| {
+            let mut stmt = self.conn.prepare(
+                "INSERT OR REPLACE INTO vectors (id, collection_id, dimension, data, metadata) VALUES (?, ?, ?, ?, ?)"
+            ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to prepare statement: {}", e)))?;
+
+            for vector in vectors {
+                let data = bincode::serialize(&vector.data)
+                    .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize vector data: {}", e)))?;
+
+                let metadata = vector.metadata.as_ref()
+                    .map(|m| serde_json::to_string(m))
+                    .transpose()
+                    .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize metadata: {}", e)))?;
+
+                stmt.execute(params![
+                    vector.id.to_string(),
+                    collection_id,
+                    vector.dimension(),
+                    data,
+                    metadata
+                ]).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to insert vector: {}", e)))?;
+            }
+
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                self.conn.execute_batch("COMMIT")
+                    .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to commit transaction: {}", e)))?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
     pub fn get_vector(&self, id: &Uuid) -> Result<Option<Vector>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, dimension, data, metadata FROM vectors WHERE id = ?"
+            "SELECT id, dimension, data, metadata FROM vectors WHERE id = ? AND tombstone = 0"
         ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to prepare query: {}", e)))?;
 
         let mut rows = stmt.query(params![id.to_string()])
@@ -103,25 +184,110 @@ impl SQLiteStorage {
 
         if let Some(row) = rows.next()
             .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to fetch row: {}", e)))? {
-            let vector = self.row_to_vector(&row)?;
+            let vector = row_to_vector(&row)?;
             Ok(Some(vector))
         } else {
             Ok(None)
         }
     }
 
+    /// Like [`Self::get_vector`], but returns the row's `version` and
+    /// ignores the tombstone flag, so a soft-deleted row's last known state
+    /// can still be inspected for conflict detection or audit purposes.
+    pub fn get_vector_with_version(&self, id: &Uuid) -> Result<Option<(Vector, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, dimension, data, metadata, version FROM vectors WHERE id = ?"
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to prepare query: {}", e)))?;
+
+        let mut rows = stmt.query(params![id.to_string()])
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to execute query: {}", e)))?;
+
+        if let Some(row) = rows.next()
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to fetch row: {}", e)))? {
+            let vector = row_to_vector(&row)?;
+            let version: i64 = row.get(4)?;
+            Ok(Some((vector, version)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Marks the row as a tombstone and bumps its version instead of
+    /// removing it, so concurrent writers can still see that a delete
+    /// happened (and when) rather than the id simply vanishing.
     pub fn delete_vector(&self, id: &Uuid) -> Result<()> {
+        self.require_writable()?;
+        let tombstoned_at = unix_timestamp();
+
         self.conn.execute(
-            "DELETE FROM vectors WHERE id = ?",
-            params![id.to_string()],
+            "UPDATE vectors SET tombstone = 1, version = version + 1, tombstoned_at = ? WHERE id = ?",
+            params![tombstoned_at, id.to_string()],
         ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to delete vector: {}", e)))?;
 
         Ok(())
     }
 
+    /// Tombstones `ids` in a single explicit transaction, the delete-side
+    /// counterpart to [`Self::insert_vectors`]. Rolls back and returns the
+    /// first error if any row fails to update.
+    pub fn delete_vectors(&self, ids: &[Uuid]) -> Result<()> {
+        self.require_writable()?;
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let tombstoned_at = unix_timestamp();
+
+        self.conn.execute_batch("BEGIN")
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to begin transaction: {}", e)))?;
+
+        let result: Result<()> = (|| {
+            let mut stmt = self.conn.prepare(
+                "UPDATE vectors SET tombstone = 1, version = version + 1, tombstoned_at = ? WHERE id = ?"
+            ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to prepare statement: {}", e)))?;
+
+            for id in ids {
+                stmt.execute(params![tombstoned_at, id.to_string()])
+                    .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to delete vector: {}", e)))?;
+            }
+
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                self.conn.execute_batch("COMMIT")
+                    .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to commit transaction: {}", e)))?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    /// Permanently removes tombstoned rows whose `tombstoned_at` is older
+    /// than `before`, reclaiming the space `delete_vector` leaves behind.
+    /// Returns the number of rows purged.
+    pub fn purge_tombstones(&self, before: SystemTime) -> Result<usize> {
+        self.require_writable()?;
+        let cutoff = before
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        let purged = self.conn.execute(
+            "DELETE FROM vectors WHERE tombstone = 1 AND tombstoned_at < ?",
+            params![cutoff],
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to purge tombstones: {}", e)))?;
+
+        Ok(purged)
+    }
+
     pub fn get_all_vectors(&self) -> Result<Vec<Vector>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, dimension, data, metadata FROM vectors ORDER BY created_at"
+            "SELECT id, dimension, data, metadata FROM vectors WHERE tombstone = 0 ORDER BY created_at"
         ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to prepare query: {}", e)))?;
 
         let rows = stmt.query([])
@@ -130,7 +296,7 @@ impl SQLiteStorage {
         let mut vectors = Vec::new();
         for row in rows {
             let row = row.map_err(|e| crate::VectorDBError::StorageError(format!("Failed to fetch row: {}", e)))?;
-            let vector = self.row_to_vector(&row)?;
+            let vector = row_to_vector(&row)?;
             vectors.push(vector);
         }
 
@@ -139,7 +305,7 @@ impl SQLiteStorage {
 
     pub fn count_vectors(&self) -> Result<usize> {
         let count: i64 = self.conn.query_row(
-            "SELECT COUNT(*) FROM vectors",
+            "SELECT COUNT(*) FROM vectors WHERE tombstone = 0",
             [],
             |row| row.get(0),
         ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to count vectors: {}", e)))?;
@@ -148,6 +314,7 @@ impl SQLiteStorage {
     }
 
     pub fn set_system_info(&self, key: &str, value: &str) -> Result<()> {
+        self.require_writable()?;
         self.conn.execute(
             "INSERT OR REPLACE INTO system_info (key, value, updated_at) VALUES (?, ?, CURRENT_TIMESTAMP)",
             params![key, value],
@@ -167,39 +334,345 @@ impl SQLiteStorage {
         Ok(value)
     }
 
-    fn get_collection_id(&self) -> Result<i64> {
-        let id: i64 = self.conn.query_row(
-            "SELECT id FROM collections WHERE name = ?",
-            params![self.collection_name],
+    /// Records `id` as the canonical holder of `hash`, so a future
+    /// `lookup_content_hash` call can find it without scanning every vector.
+    pub fn record_content_hash(&self, hash: u64, id: &Uuid) -> Result<()> {
+        self.require_writable()?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO content_hashes (hash, id) VALUES (?, ?)",
+            params![hash as i64, id.to_string()],
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to record content hash: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Looks up the id of the vector previously recorded under `hash`, if any.
+    pub fn lookup_content_hash(&self, hash: u64) -> Result<Option<Uuid>> {
+        let id_str: Option<String> = self.conn.query_row(
+            "SELECT id FROM content_hashes WHERE hash = ?",
+            params![hash as i64],
             |row| row.get(0),
-        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to get collection ID: {}", e)))?;
+        ).optional()
+        .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to look up content hash: {}", e)))?;
 
-        Ok(id)
+        id_str.map(|s| Uuid::parse_str(&s)
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to parse UUID: {}", e))))
+            .transpose()
+    }
+
+    /// Drops `hash`'s mapping, e.g. once its canonical vector is deleted.
+    pub fn remove_content_hash(&self, hash: u64) -> Result<()> {
+        self.require_writable()?;
+        self.conn.execute(
+            "DELETE FROM content_hashes WHERE hash = ?",
+            params![hash as i64],
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to remove content hash: {}", e)))?;
+
+        Ok(())
+    }
+
+    fn get_collection_id(&self) -> Result<i64> {
+        get_collection_id(&self.conn, &self.collection_name)
     }
 
     fn row_to_vector(&self, row: &Row) -> Result<Vector> {
-        let id_str: String = row.get(0)?;
-        let dimension: i64 = row.get(1)?;
-        let data_blob: Vec<u8> = row.get(2)?;
-        let metadata_str: Option<String> = row.get(3)?;
+        row_to_vector(row)
+    }
+}
 
-        let id = Uuid::parse_str(&id_str)
-            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to parse UUID: {}", e)))?;
+/// Seconds since the Unix epoch, used as the `tombstoned_at` watermark that
+/// `purge_tombstones` compares against.
+fn unix_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
 
-        let data = bincode::deserialize(&data_blob)
-            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize vector data: {}", e)))?;
+fn get_collection_id(conn: &Connection, collection_name: &str) -> Result<i64> {
+    let id: i64 = conn.query_row(
+        "SELECT id FROM collections WHERE name = ?",
+        params![collection_name],
+        |row| row.get(0),
+    ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to get collection ID: {}", e)))?;
 
-        let metadata = if let Some(metadata_str) = metadata_str {
-            Some(serde_json::from_str(&metadata_str)
-                .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize metadata: {}", e)))?)
-        } else {
-            None
+    Ok(id)
+}
+
+fn row_to_vector(row: &Row) -> Result<Vector> {
+    let id_str: String = row.get(0)?;
+    let dimension: i64 = row.get(1)?;
+    let data_blob: Vec<u8> = row.get(2)?;
+    let metadata_str: Option<String> = row.get(3)?;
+
+    let id = Uuid::parse_str(&id_str)
+        .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to parse UUID: {}", e)))?;
+
+    let data = bincode::deserialize(&data_blob)
+        .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize vector data: {}", e)))?;
+
+    let metadata = if let Some(metadata_str) = metadata_str {
+        Some(serde_json::from_str(&metadata_str)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize metadata: {}", e)))?)
+    } else {
+        None
+    };
+
+    Ok(Vector {
+        id,
+        data,
+        metadata,
+    })
+}
+
+/// Thread-safe connection pool around a single SQLite database: several
+/// reader connections serve `get_vector`/`get_all_vectors`/`count_vectors`
+/// round-robin, one writer connection serializes every mutation, and a
+/// background thread periodically checkpoints the WAL, runs `ANALYZE`, and
+/// purges expired tombstones. This is the reader-pool + writer + maintenance-
+/// thread split that lets an embedding service fan similarity reads out
+/// across threads while a single writer keeps inserts ordered.
+pub struct PooledSQLiteStorage {
+    readers: Vec<Mutex<Connection>>,
+    next_reader: AtomicUsize,
+    writer: Mutex<Connection>,
+    collection_name: String,
+    stop_maintenance: Arc<AtomicBool>,
+    maintenance_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl PooledSQLiteStorage {
+    /// Opens `reader_count` read-only connections and one read-write
+    /// connection to `db_path`, then spawns a maintenance thread that wakes
+    /// up every `maintenance_interval` to checkpoint the WAL, `ANALYZE` the
+    /// database, and purge tombstones older than `tombstone_retention`.
+    pub fn new<P: AsRef<Path>>(
+        db_path: P,
+        collection_name: &str,
+        reader_count: usize,
+        maintenance_interval: Duration,
+        tombstone_retention: Duration,
+    ) -> Result<Self> {
+        let db_path = db_path.as_ref();
+        let reader_count = reader_count.max(1);
+
+        let writer = open_writer_connection(db_path)?;
+        init_tables(&writer, collection_name)?;
+
+        let mut readers = Vec::with_capacity(reader_count);
+        for _ in 0..reader_count {
+            let reader = Connection::open_with_flags(
+                db_path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to open reader connection: {}", e)))?;
+            readers.push(Mutex::new(reader));
+        }
+
+        let stop_maintenance = Arc::new(AtomicBool::new(false));
+        let maintenance_thread = {
+            let db_path = db_path.to_path_buf();
+            let stop_maintenance = Arc::clone(&stop_maintenance);
+            thread::spawn(move || {
+                while !stop_maintenance.load(Ordering::Relaxed) {
+                    thread::sleep(maintenance_interval);
+                    if stop_maintenance.load(Ordering::Relaxed) {
+                        break;
+                    }
+
+                    if let Ok(conn) = Connection::open(&db_path) {
+                        let _ = conn.execute_batch("PRAGMA wal_checkpoint(PASSIVE)");
+                        let _ = conn.execute_batch("ANALYZE");
+
+                        let cutoff = SystemTime::now()
+                            .checked_sub(tombstone_retention)
+                            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                            .map(|d| d.as_secs() as i64)
+                            .unwrap_or(0);
+                        let _ = conn.execute(
+                            "DELETE FROM vectors WHERE tombstone = 1 AND tombstoned_at < ?",
+                            params![cutoff],
+                        );
+                    }
+                }
+            })
         };
 
-        Ok(Vector {
-            id,
-            data,
-            metadata,
+        Ok(Self {
+            readers,
+            next_reader: AtomicUsize::new(0),
+            writer: Mutex::new(writer),
+            collection_name: collection_name.to_string(),
+            stop_maintenance,
+            maintenance_thread: Some(maintenance_thread),
         })
     }
-} 
\ No newline at end of file
+
+    /// Hands out the next reader connection in round-robin order, for
+    /// callers that want to run a raw read query across the pool themselves.
+    pub fn reader(&self) -> &Mutex<Connection> {
+        let index = self.next_reader.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        &self.readers[index]
+    }
+
+    /// The single writer connection every mutation is serialized through.
+    pub fn writer(&self) -> &Mutex<Connection> {
+        &self.writer
+    }
+
+    pub fn insert_vector(&self, vector: &Vector) -> Result<()> {
+        let conn = self.writer.lock().unwrap();
+        let collection_id = get_collection_id(&conn, &self.collection_name)?;
+
+        let data = bincode::serialize(&vector.data)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize vector data: {}", e)))?;
+        let metadata = vector.metadata.as_ref()
+            .map(|m| serde_json::to_string(m))
+            .transpose()
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize metadata: {}", e)))?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO vectors (id, collection_id, dimension, data, metadata) VALUES (?, ?, ?, ?, ?)",
+            params![
+                vector.id.to_string(),
+                collection_id,
+                vector.dimension(),
+                data,
+                metadata
+            ],
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to insert vector: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub fn delete_vector(&self, id: &Uuid) -> Result<()> {
+        let conn = self.writer.lock().unwrap();
+        let tombstoned_at = unix_timestamp();
+
+        conn.execute(
+            "UPDATE vectors SET tombstone = 1, version = version + 1, tombstoned_at = ? WHERE id = ?",
+            params![tombstoned_at, id.to_string()],
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to delete vector: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub fn get_vector(&self, id: &Uuid) -> Result<Option<Vector>> {
+        let conn = self.reader().lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, dimension, data, metadata FROM vectors WHERE id = ? AND tombstone = 0"
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to prepare query: {}", e)))?;
+
+        let mut rows = stmt.query(params![id.to_string()])
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to execute query: {}", e)))?;
+
+        if let Some(row) = rows.next()
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to fetch row: {}", e)))? {
+            Ok(Some(row_to_vector(&row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn get_all_vectors(&self) -> Result<Vec<Vector>> {
+        let conn = self.reader().lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, dimension, data, metadata FROM vectors WHERE tombstone = 0 ORDER BY created_at"
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to prepare query: {}", e)))?;
+
+        let rows = stmt.query([])
+            .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to execute query: {}", e)))?;
+
+        let mut vectors = Vec::new();
+        for row in rows {
+            let row = row.map_err(|e| crate::VectorDBError::StorageError(format!("Failed to fetch row: {}", e)))?;
+            vectors.push(row_to_vector(&row)?);
+        }
+
+        Ok(vectors)
+    }
+
+    pub fn count_vectors(&self) -> Result<usize> {
+        let conn = self.reader().lock().unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM vectors WHERE tombstone = 0",
+            [],
+            |row| row.get(0),
+        ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to count vectors: {}", e)))?;
+
+        Ok(count as usize)
+    }
+}
+
+impl Drop for PooledSQLiteStorage {
+    fn drop(&mut self) {
+        self.stop_maintenance.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.maintenance_thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn open_writer_connection(db_path: &Path) -> Result<Connection> {
+    let conn = Connection::open(db_path)
+        .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to open SQLite database: {}", e)))?;
+
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to set journal_mode pragma: {}", e)))?;
+    conn.pragma_update(None, "synchronous", "NORMAL")
+        .map_err(|e| crate::VectorDBError::StorageError(format!("Failed to set synchronous pragma: {}", e)))?;
+
+    Ok(conn)
+}
+
+fn init_tables(conn: &Connection, collection_name: &str) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS collections (
+            id INTEGER PRIMARY KEY,
+            name TEXT UNIQUE NOT NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to create collections table: {}", e)))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS vectors (
+            id TEXT PRIMARY KEY,
+            collection_id INTEGER NOT NULL,
+            dimension INTEGER NOT NULL,
+            data BLOB NOT NULL,
+            metadata TEXT,
+            version INTEGER NOT NULL DEFAULT 1,
+            tombstone INTEGER NOT NULL DEFAULT 0,
+            tombstoned_at INTEGER,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (collection_id) REFERENCES collections (id)
+        )",
+        [],
+    ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to create vectors table: {}", e)))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS system_info (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to create system_info table: {}", e)))?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS content_hashes (
+            hash INTEGER PRIMARY KEY,
+            id TEXT NOT NULL
+        )",
+        [],
+    ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to create content_hashes table: {}", e)))?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO collections (name, updated_at) VALUES (?, CURRENT_TIMESTAMP)",
+        params![collection_name],
+    ).map_err(|e| crate::VectorDBError::StorageError(format!("Failed to insert collection: {}", e)))?;
+
+    Ok(())
+}
\ No newline at end of file
@@ -1,12 +1,22 @@
 use crate::{vector::Vector, Result};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
+/// Returns owned `Vector`s rather than references so implementations can
+/// guard their data behind locks (e.g. [`BucketMapStorage`]) without
+/// fighting the borrow checker over a guard that would need to outlive the
+/// call.
 pub trait Storage {
     fn insert(&mut self, vector: Vector) -> Result<()>;
-    fn get(&self, id: &Uuid) -> Option<&Vector>;
+    fn get(&self, id: &Uuid) -> Option<Vector>;
     fn delete(&mut self, id: &Uuid) -> Result<()>;
-    fn all_vectors(&self) -> Vec<&Vector>;
+    fn all_vectors(&self) -> Vec<Vector>;
     fn count(&self) -> usize;
 }
 
@@ -32,8 +42,8 @@ impl Storage for InMemoryStorage {
         Ok(())
     }
 
-    fn get(&self, id: &Uuid) -> Option<&Vector> {
-        self.vectors.get(id)
+    fn get(&self, id: &Uuid) -> Option<Vector> {
+        self.vectors.get(id).cloned()
     }
 
     fn delete(&mut self, id: &Uuid) -> Result<()> {
@@ -41,8 +51,8 @@ impl Storage for InMemoryStorage {
         Ok(())
     }
 
-    fn all_vectors(&self) -> Vec<&Vector> {
-        self.vectors.values().collect()
+    fn all_vectors(&self) -> Vec<Vector> {
+        self.vectors.values().cloned().collect()
     }
 
     fn count(&self) -> usize {
@@ -54,4 +64,393 @@ impl Default for InMemoryStorage {
     fn default() -> Self {
         Self::new()
     }
+}
+
+/// Configures a [`BucketMapStorage`].
+#[derive(Debug, Clone)]
+pub struct BucketMapConfig {
+    /// Initial number of buckets, rounded up to the next
+    /// power of two since bucket selection relies on masking, not modulo.
+    pub max_buckets: usize,
+    /// Initial capacity reserved in each bucket's map.
+    pub bucket_initial_capacity: usize,
+    /// Number of entries a bucket may hold before it's split in two. `None`
+    /// disables splitting, matching the original fixed-size behavior.
+    pub split_threshold: Option<usize>,
+    /// The directory never grows past `2^max_buckets_pow2` slots, even if a
+    /// bucket keeps overflowing `split_threshold`.
+    pub max_buckets_pow2: u32,
+    /// When set, each bucket is mirrored to its own memory-mapped segment
+    /// file under this directory, so a restart can reload bucket contents
+    /// instead of starting empty. `None` keeps everything in memory only.
+    pub segment_dir: Option<PathBuf>,
+}
+
+impl Default for BucketMapConfig {
+    fn default() -> Self {
+        Self {
+            max_buckets: 16,
+            bucket_initial_capacity: 0,
+            split_threshold: None,
+            max_buckets_pow2: 16,
+            segment_dir: None,
+        }
+    }
+}
+
+/// One partition of a [`BucketMapStorage`]'s directory. `local_depth` is the
+/// number of low bits of `hash(id)` that every key in this bucket already
+/// agrees on — the extendible-hashing invariant that lets the directory
+/// double without rehashing buckets that aren't the one overflowing.
+struct Bucket {
+    data: HashMap<Uuid, Vector>,
+    local_depth: u32,
+    segment: Option<BucketSegment>,
+}
+
+impl Bucket {
+    fn new(local_depth: u32, segment: Option<BucketSegment>) -> Result<Self> {
+        let data = match &segment {
+            Some(segment) => segment.load()?,
+            None => HashMap::new(),
+        };
+        Ok(Self { data, local_depth, segment })
+    }
+
+    fn persist(&self) -> Result<()> {
+        if let Some(segment) = &self.segment {
+            segment.store(&self.data)?;
+        }
+        Ok(())
+    }
+}
+
+/// A memory-mapped, whole-bucket-at-a-time backing file: loads deserialize
+/// the mapped bytes, and stores rewrite the file and remap it. This trades
+/// incremental-write efficiency for simplicity, the same tradeoff
+/// [`super::persistence::PersistentStorage`] makes for its single JSON file.
+struct BucketSegment {
+    id: usize,
+    path: PathBuf,
+}
+
+impl BucketSegment {
+    fn load(&self) -> Result<HashMap<Uuid, Vector>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let file = OpenOptions::new().read(true).open(&self.path)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open bucket segment: {}", e)))?;
+
+        if file.metadata().map(|m| m.len()).unwrap_or(0) == 0 {
+            return Ok(HashMap::new());
+        }
+
+        let mmap = unsafe {
+            memmap2::Mmap::map(&file)
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to mmap bucket segment: {}", e)))?
+        };
+
+        bincode::deserialize(&mmap[..])
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to deserialize bucket segment: {}", e)))
+    }
+
+    fn store(&self, data: &HashMap<Uuid, Vector>) -> Result<()> {
+        let bytes = bincode::serialize(data)
+            .map_err(|e| crate::VectorDBError::SerializationError(format!("Failed to serialize bucket segment: {}", e)))?;
+
+        let file = OpenOptions::new().create(true).read(true).write(true).truncate(true).open(&self.path)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to open bucket segment: {}", e)))?;
+        file.set_len(bytes.len().max(1) as u64)
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to size bucket segment: {}", e)))?;
+
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        let mut mmap = unsafe {
+            memmap2::MmapMut::map_mut(&file)
+                .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to mmap bucket segment: {}", e)))?
+        };
+        mmap[..bytes.len()].copy_from_slice(&bytes);
+        mmap.flush()
+            .map_err(|e| crate::VectorDBError::PersistenceError(format!("Failed to flush bucket segment: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// A [`Storage`] implementation that partitions vectors across `2^n`
+/// independently-locked buckets instead of one `HashMap` behind a single
+/// lock, using extendible hashing so a single overflowing bucket can split
+/// without rehashing the rest of the directory. Each bucket is selected by
+/// the low bits of `hash(id)`, so concurrent readers touching different ids
+/// mostly contend on different locks rather than one global one.
+pub struct BucketMapStorage {
+    directory: Vec<Arc<RwLock<Bucket>>>,
+    global_depth: u32,
+    config: BucketMapConfig,
+    next_segment_id: usize,
+}
+
+/// On-disk record of a `segment_dir`-backed [`BucketMapStorage`]'s directory
+/// shape, written by `persist_manifest` whenever a split changes it. Without
+/// this, `with_config` has no way to know that a bucket split ever happened
+/// — it would just rebuild the default, unsplit directory and reload each
+/// segment file as its own independent bucket, orphaning every
+/// split-created segment and routing ids by the reset (lower) depth.
+#[derive(Debug, Serialize, Deserialize)]
+struct DirectoryManifest {
+    global_depth: u32,
+    next_segment_id: usize,
+    /// One entry per directory slot, in slot order: the segment id backing
+    /// it and that bucket's local_depth. Slots still aliasing the same
+    /// bucket (`local_depth < global_depth`) repeat that bucket's segment
+    /// id, so reloading can share one `Bucket`/`Arc` across them.
+    slots: Vec<(usize, u32)>,
+}
+
+impl BucketMapStorage {
+    pub fn new() -> Self {
+        Self::with_config(BucketMapConfig::default())
+    }
+
+    pub fn with_config(config: BucketMapConfig) -> Self {
+        if let Some(dir) = &config.segment_dir {
+            if let Some(storage) = Self::load_from_manifest(dir, &config) {
+                return storage;
+            }
+        }
+
+        let num_buckets = config.max_buckets.max(1).next_power_of_two();
+        let global_depth = num_buckets.trailing_zeros();
+
+        let mut storage = Self {
+            directory: Vec::with_capacity(num_buckets),
+            global_depth,
+            config,
+            next_segment_id: 0,
+        };
+
+        for _ in 0..num_buckets {
+            let segment = storage.new_segment();
+            let bucket = Bucket::new(global_depth, segment).unwrap_or_else(|_| {
+                Bucket { data: HashMap::with_capacity(storage.config.bucket_initial_capacity), local_depth: global_depth, segment: None }
+            });
+            storage.directory.push(Arc::new(RwLock::new(bucket)));
+        }
+
+        storage.persist_manifest();
+        storage
+    }
+
+    /// The number of directory slots vectors are partitioned across, always
+    /// a power of two. Slots can alias the same underlying bucket until
+    /// that bucket is itself split.
+    pub fn num_buckets(&self) -> usize {
+        1 << self.global_depth
+    }
+
+    fn new_segment(&mut self) -> Option<BucketSegment> {
+        let dir = self.config.segment_dir.as_ref()?;
+        let id = self.next_segment_id;
+        self.next_segment_id += 1;
+        Some(BucketSegment { id, path: dir.join(format!("bucket-{:04}.seg", id)) })
+    }
+
+    fn manifest_path(dir: &Path) -> PathBuf {
+        dir.join("directory.manifest")
+    }
+
+    /// Writes out the directory's current shape — global depth, per-slot
+    /// local depth, and which segment each slot maps to — so `with_config`
+    /// can rebuild the exact post-split layout on the next restart instead
+    /// of starting over at the configured initial bucket count. A no-op
+    /// without `segment_dir`, or if any slot's bucket isn't backed by a
+    /// segment (nothing to reload from in that case anyway).
+    fn persist_manifest(&self) {
+        let Some(dir) = self.config.segment_dir.clone() else { return };
+
+        let mut slots = Vec::with_capacity(self.directory.len());
+        for bucket in &self.directory {
+            let Ok(bucket) = bucket.read() else { return };
+            let Some(segment) = &bucket.segment else { return };
+            slots.push((segment.id, bucket.local_depth));
+        }
+
+        let manifest = DirectoryManifest {
+            global_depth: self.global_depth,
+            next_segment_id: self.next_segment_id,
+            slots,
+        };
+        if let Ok(bytes) = bincode::serialize(&manifest) {
+            let _ = std::fs::write(Self::manifest_path(&dir), bytes);
+        }
+    }
+
+    /// Rebuilds a directory previously written by `persist_manifest`,
+    /// sharing one `Bucket`/`Arc` per distinct segment id so slots that
+    /// still alias the same bucket (`local_depth < global_depth`) keep
+    /// aliasing it, exactly as `split()` left them. Returns `None` if there
+    /// is no manifest yet (fresh directory) or it can't be read back.
+    fn load_from_manifest(dir: &Path, config: &BucketMapConfig) -> Option<Self> {
+        let bytes = std::fs::read(Self::manifest_path(dir)).ok()?;
+        let manifest: DirectoryManifest = bincode::deserialize(&bytes).ok()?;
+
+        let mut buckets_by_segment: HashMap<usize, Arc<RwLock<Bucket>>> = HashMap::new();
+        let mut directory = Vec::with_capacity(manifest.slots.len());
+        for (segment_id, local_depth) in manifest.slots {
+            let bucket_arc = match buckets_by_segment.get(&segment_id) {
+                Some(bucket_arc) => bucket_arc.clone(),
+                None => {
+                    let segment = BucketSegment { id: segment_id, path: dir.join(format!("bucket-{:04}.seg", segment_id)) };
+                    let bucket = Bucket::new(local_depth, Some(segment)).ok()?;
+                    let bucket_arc = Arc::new(RwLock::new(bucket));
+                    buckets_by_segment.insert(segment_id, bucket_arc.clone());
+                    bucket_arc
+                }
+            };
+            directory.push(bucket_arc);
+        }
+
+        Some(Self {
+            directory,
+            global_depth: manifest.global_depth,
+            config: config.clone(),
+            next_segment_id: manifest.next_segment_id,
+        })
+    }
+
+    fn slot(&self, id: &Uuid) -> usize {
+        (hash_id(id) as usize) & (self.num_buckets() - 1)
+    }
+
+    fn poisoned_lock_error() -> crate::VectorDBError {
+        crate::VectorDBError::StorageError("BucketMapStorage bucket lock was poisoned".to_string())
+    }
+
+    /// Splits the bucket at `slot` in two, doubling the directory first if
+    /// every slot still points at it (`local_depth == global_depth`).
+    /// Buckets other than the one overflowing are never touched.
+    fn split(&mut self, slot: usize) -> Result<()> {
+        let Some(split_threshold) = self.config.split_threshold else { return Ok(()) };
+
+        let bucket_arc = self.directory[slot].clone();
+        let local_depth = bucket_arc.read().map_err(|_| Self::poisoned_lock_error())?.local_depth;
+        if bucket_arc.read().map_err(|_| Self::poisoned_lock_error())?.data.len() <= split_threshold {
+            return Ok(());
+        }
+
+        if local_depth >= self.config.max_buckets_pow2 {
+            // Can't grow further; accept the oversized bucket.
+            return Ok(());
+        }
+
+        if local_depth == self.global_depth {
+            let old_len = self.directory.len();
+            self.directory.reserve(old_len);
+            for i in 0..old_len {
+                self.directory.push(self.directory[i].clone());
+            }
+            self.global_depth += 1;
+        }
+
+        let new_local_depth = local_depth + 1;
+        let split_bit = local_depth;
+
+        let (kept, moved) = {
+            let mut bucket = bucket_arc.write().map_err(|_| Self::poisoned_lock_error())?;
+            let mut moved = HashMap::new();
+            bucket.data.retain(|id, vector| {
+                if (hash_id(id) >> split_bit) & 1 == 1 {
+                    moved.insert(*id, vector.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            bucket.local_depth = new_local_depth;
+            bucket.persist()?;
+            (bucket_arc.clone(), moved)
+        };
+
+        let new_segment = self.new_segment();
+        let new_bucket = Bucket { data: moved, local_depth: new_local_depth, segment: new_segment };
+        new_bucket.persist()?;
+        let new_bucket_arc = Arc::new(RwLock::new(new_bucket));
+
+        for i in 0..self.directory.len() {
+            if Arc::ptr_eq(&self.directory[i], &kept) && (i >> split_bit) & 1 == 1 {
+                self.directory[i] = new_bucket_arc.clone();
+            }
+        }
+
+        self.persist_manifest();
+        Ok(())
+    }
+}
+
+fn hash_id(id: &Uuid) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Storage for BucketMapStorage {
+    fn insert(&mut self, vector: Vector) -> Result<()> {
+        let slot = self.slot(&vector.id);
+        {
+            let mut bucket = self.directory[slot].write().map_err(|_| Self::poisoned_lock_error())?;
+            bucket.data.insert(vector.id, vector);
+            bucket.persist()?;
+        }
+        self.split(slot)
+    }
+
+    fn get(&self, id: &Uuid) -> Option<Vector> {
+        let slot = self.slot(id);
+        self.directory[slot].read().ok()?.data.get(id).cloned()
+    }
+
+    fn delete(&mut self, id: &Uuid) -> Result<()> {
+        let slot = self.slot(id);
+        let mut bucket = self.directory[slot].write().map_err(|_| Self::poisoned_lock_error())?;
+        bucket.data.remove(id);
+        bucket.persist()
+    }
+
+    fn all_vectors(&self) -> Vec<Vector> {
+        let mut seen = std::collections::HashSet::new();
+        let mut vectors = Vec::new();
+        for entry in &self.directory {
+            if !seen.insert(Arc::as_ptr(entry)) {
+                continue;
+            }
+            if let Ok(bucket) = entry.read() {
+                vectors.extend(bucket.data.values().cloned());
+            }
+        }
+        vectors
+    }
+
+    fn count(&self) -> usize {
+        let mut seen = std::collections::HashSet::new();
+        let mut total = 0;
+        for entry in &self.directory {
+            if !seen.insert(Arc::as_ptr(entry)) {
+                continue;
+            }
+            if let Ok(bucket) = entry.read() {
+                total += bucket.data.len();
+            }
+        }
+        total
+    }
+}
+
+impl Default for BucketMapStorage {
+    fn default() -> Self {
+        Self::new()
+    }
 } 
\ No newline at end of file
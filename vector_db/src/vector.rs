@@ -2,6 +2,8 @@ use serde::{Serialize, Deserialize};
 use ndarray::Array1;
 use uuid::Uuid;
 
+use crate::Result;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vector {
     pub id: Uuid,
@@ -41,4 +43,70 @@ impl Vector {
     pub fn magnitude(&self) -> f32 {
         self.data.dot(&self.data).sqrt()
     }
+
+    /// Elementwise sum of `self` and `other` as a new vector. Errors if
+    /// their dimensions don't match.
+    pub fn add(&self, other: &Vector) -> Result<Vector> {
+        self.check_same_dimension(other)?;
+        Ok(Vector::new(&self.data + &other.data))
+    }
+
+    /// Elementwise difference of `self` and `other` as a new vector. Errors
+    /// if their dimensions don't match.
+    pub fn sub(&self, other: &Vector) -> Result<Vector> {
+        self.check_same_dimension(other)?;
+        Ok(Vector::new(&self.data - &other.data))
+    }
+
+    /// Multiplies every component by `factor`, as a new vector.
+    pub fn scale(&self, factor: f32) -> Vector {
+        Vector::new(&self.data * factor)
+    }
+
+    /// Adds `scalar` to every component, as a new vector.
+    pub fn add_scalar(&self, scalar: f32) -> Vector {
+        Vector::new(&self.data + scalar)
+    }
+
+    /// Averages `vectors` elementwise into a single centroid vector. Errors
+    /// if `vectors` is empty or its vectors don't all share one dimension.
+    pub fn centroid(vectors: &[Vector]) -> Result<Vector> {
+        let first = vectors.first().ok_or_else(|| {
+            crate::VectorDBError::StorageError("Cannot compute centroid of an empty slice of vectors".to_string())
+        })?;
+        let dimension = first.dimension();
+
+        let mut sum = Array1::<f32>::zeros(dimension);
+        for vector in vectors {
+            if vector.dimension() != dimension {
+                return Err(crate::VectorDBError::StorageError(format!(
+                    "Cannot compute centroid: vector has dimension {} but expected {}",
+                    vector.dimension(),
+                    dimension
+                )));
+            }
+            sum += &vector.data;
+        }
+
+        Ok(Vector::new(sum / vectors.len() as f32))
+    }
+
+    /// xxh3_64 of `data`'s bincode-serialized bytes, used by
+    /// [`crate::collection_manager::CollectionManager::add_vector`] to
+    /// detect an already-stored identical payload.
+    pub fn content_hash(&self) -> u64 {
+        let bytes = bincode::serialize(&self.data).unwrap_or_default();
+        xxhash_rust::xxh3::xxh3_64(&bytes)
+    }
+
+    fn check_same_dimension(&self, other: &Vector) -> Result<()> {
+        if self.dimension() != other.dimension() {
+            return Err(crate::VectorDBError::StorageError(format!(
+                "Cannot operate on vectors of different dimensions: {} vs {}",
+                self.dimension(),
+                other.dimension()
+            )));
+        }
+        Ok(())
+    }
 } 
\ No newline at end of file
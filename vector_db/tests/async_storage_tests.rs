@@ -0,0 +1,92 @@
+use vector_db::{async_storage::{AsyncStorage, InMemoryAsyncStorage, RedbStorage}, vector::Vector, utils::generate_random_vectors};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_in_memory_async_storage_insert_and_get() {
+    let storage = InMemoryAsyncStorage::new();
+
+    let data = generate_random_vectors(4, 1).into_iter().next().unwrap();
+    let vector = Vector::new(data);
+    storage.insert(vector.clone()).await.unwrap();
+
+    let fetched = storage.get(&vector.id).await.unwrap().unwrap();
+    assert_eq!(fetched.data, vector.data);
+    assert_eq!(storage.count().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_in_memory_async_storage_delete_and_all_vectors() {
+    let storage = InMemoryAsyncStorage::new();
+
+    let vectors: Vec<_> = generate_random_vectors(4, 3).into_iter().map(Vector::new).collect();
+    for vector in &vectors {
+        storage.insert(vector.clone()).await.unwrap();
+    }
+
+    storage.delete(&vectors[0].id).await.unwrap();
+
+    assert!(storage.get(&vectors[0].id).await.unwrap().is_none());
+    let all = storage.all_vectors().await.unwrap();
+    assert_eq!(all.len(), 2);
+}
+
+#[tokio::test]
+async fn test_redb_storage_insert_get_delete() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("vectors.redb");
+    let storage = RedbStorage::new(&db_path).unwrap();
+
+    let vectors: Vec<_> = generate_random_vectors(8, 5).into_iter().map(Vector::new).collect();
+    for vector in &vectors {
+        storage.insert(vector.clone()).await.unwrap();
+    }
+
+    assert_eq!(storage.count().await.unwrap(), 5);
+
+    let fetched = storage.get(&vectors[0].id).await.unwrap().unwrap();
+    assert_eq!(fetched.data, vectors[0].data);
+
+    storage.delete(&vectors[0].id).await.unwrap();
+    assert!(storage.get(&vectors[0].id).await.unwrap().is_none());
+    assert_eq!(storage.count().await.unwrap(), 4);
+}
+
+#[tokio::test]
+async fn test_redb_storage_persists_across_instances() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("vectors.redb");
+
+    let vector = Vector::new(generate_random_vectors(4, 1).into_iter().next().unwrap());
+    {
+        let storage = RedbStorage::new(&db_path).unwrap();
+        storage.insert(vector.clone()).await.unwrap();
+    }
+
+    let storage = RedbStorage::new(&db_path).unwrap();
+    let fetched = storage.get(&vector.id).await.unwrap().unwrap();
+    assert_eq!(fetched.data, vector.data);
+}
+
+#[tokio::test]
+async fn test_redb_storage_concurrent_inserts_are_consistent() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("vectors.redb");
+    let storage = Arc::new(RedbStorage::new(&db_path).unwrap());
+
+    let vectors: Vec<_> = generate_random_vectors(8, 20).into_iter().map(Vector::new).collect();
+
+    let mut handles = Vec::new();
+    for vector in vectors.clone() {
+        let storage = Arc::clone(&storage);
+        handles.push(tokio::spawn(async move {
+            storage.insert(vector).await.unwrap();
+        }));
+    }
+
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(storage.count().await.unwrap(), 20);
+}
@@ -1,11 +1,13 @@
 use vector_db::{
     vector::Vector,
-    storage::InMemoryStorage,
-    index::BruteForceIndex,
+    storage::{InMemoryStorage, BucketMapStorage, BucketMapConfig},
+    index::{BruteForceIndex, LshIndex},
     query::QueryEngine,
     utils::{generate_random_vectors, cosine_similarity},
+    Index, Storage,
 };
 use ndarray::Array1;
+use tempfile::TempDir;
 
 #[test]
 fn test_core_functionality() {
@@ -44,4 +46,187 @@ fn test_random_vectors() {
     let vectors = generate_random_vectors(128, 10);
     assert_eq!(vectors.len(), 10);
     assert_eq!(vectors[0].len(), 128);
+}
+
+#[test]
+fn test_vector_arithmetic() {
+    let a = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0]));
+    let b = Vector::new(Array1::from_vec(vec![4.0, 5.0, 6.0]));
+
+    let sum = a.add(&b).unwrap();
+    assert_eq!(sum.data, Array1::from_vec(vec![5.0, 7.0, 9.0]));
+
+    let diff = a.sub(&b).unwrap();
+    assert_eq!(diff.data, Array1::from_vec(vec![-3.0, -3.0, -3.0]));
+
+    let scaled = a.scale(2.0);
+    assert_eq!(scaled.data, Array1::from_vec(vec![2.0, 4.0, 6.0]));
+
+    let shifted = a.add_scalar(1.0);
+    assert_eq!(shifted.data, Array1::from_vec(vec![2.0, 3.0, 4.0]));
+}
+
+#[test]
+fn test_vector_arithmetic_dimension_mismatch_errors() {
+    let a = Vector::new(Array1::from_vec(vec![1.0, 2.0]));
+    let b = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0]));
+
+    assert!(a.add(&b).is_err());
+    assert!(a.sub(&b).is_err());
+}
+
+#[test]
+fn test_vector_centroid() {
+    let vectors = vec![
+        Vector::new(Array1::from_vec(vec![1.0, 1.0])),
+        Vector::new(Array1::from_vec(vec![3.0, 3.0])),
+    ];
+
+    let centroid = Vector::centroid(&vectors).unwrap();
+    assert_eq!(centroid.data, Array1::from_vec(vec![2.0, 2.0]));
+}
+
+#[test]
+fn test_vector_centroid_empty_errors() {
+    let vectors: Vec<Vector> = vec![];
+    assert!(Vector::centroid(&vectors).is_err());
+}
+
+#[test]
+fn test_vector_centroid_dimension_mismatch_errors() {
+    let vectors = vec![
+        Vector::new(Array1::from_vec(vec![1.0, 1.0])),
+        Vector::new(Array1::from_vec(vec![1.0, 1.0, 1.0])),
+    ];
+    assert!(Vector::centroid(&vectors).is_err());
+}
+
+#[test]
+fn test_bucket_map_storage_rounds_up_to_power_of_two() {
+    let storage = BucketMapStorage::with_config(BucketMapConfig {
+        max_buckets: 10,
+        bucket_initial_capacity: 0,
+        ..Default::default()
+    });
+    assert_eq!(storage.num_buckets(), 16);
+}
+
+#[test]
+fn test_bucket_map_storage_splits_overflowing_bucket_without_rehashing_others() {
+    let mut storage = BucketMapStorage::with_config(BucketMapConfig {
+        max_buckets: 2,
+        split_threshold: Some(4),
+        max_buckets_pow2: 8,
+        ..Default::default()
+    });
+
+    let vectors: Vec<Vector> = generate_random_vectors(4, 200).into_iter().map(Vector::new).collect();
+    for v in &vectors {
+        storage.insert(v.clone()).unwrap();
+    }
+
+    assert!(storage.num_buckets() > 2);
+    assert_eq!(storage.count(), 200);
+    for v in &vectors {
+        let fetched = storage.get(&v.id).unwrap();
+        assert_eq!(fetched.data, v.data);
+    }
+}
+
+#[test]
+fn test_bucket_map_storage_insert_get_delete() {
+    let mut storage = BucketMapStorage::new();
+    let vectors: Vec<Vector> = generate_random_vectors(8, 50).into_iter().map(Vector::new).collect();
+
+    for v in &vectors {
+        storage.insert(v.clone()).unwrap();
+    }
+
+    assert_eq!(storage.count(), 50);
+    assert_eq!(storage.all_vectors().len(), 50);
+
+    for v in &vectors {
+        let fetched = storage.get(&v.id).unwrap();
+        assert_eq!(fetched.data, v.data);
+    }
+
+    storage.delete(&vectors[0].id).unwrap();
+    assert_eq!(storage.count(), 49);
+    assert!(storage.get(&vectors[0].id).is_none());
+}
+
+#[test]
+fn test_bucket_map_storage_get_missing_returns_none() {
+    let storage = BucketMapStorage::new();
+    assert!(storage.get(&uuid::Uuid::new_v4()).is_none());
+}
+
+#[test]
+fn test_bucket_map_storage_reloads_from_segment_dir_after_restart() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = || BucketMapConfig {
+        max_buckets: 4,
+        segment_dir: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    let vectors: Vec<Vector> = generate_random_vectors(4, 20).into_iter().map(Vector::new).collect();
+    {
+        let mut storage = BucketMapStorage::with_config(config());
+        for v in &vectors {
+            storage.insert(v.clone()).unwrap();
+        }
+    }
+
+    let reopened = BucketMapStorage::with_config(config());
+    assert_eq!(reopened.count(), 20);
+    for v in &vectors {
+        let fetched = reopened.get(&v.id).unwrap();
+        assert_eq!(fetched.data, v.data);
+    }
+}
+
+#[test]
+fn test_bucket_map_storage_reloads_split_directory_after_restart() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = || BucketMapConfig {
+        max_buckets: 2,
+        split_threshold: Some(4),
+        max_buckets_pow2: 8,
+        segment_dir: Some(temp_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    let vectors: Vec<Vector> = generate_random_vectors(4, 200).into_iter().map(Vector::new).collect();
+    let num_buckets_before_restart = {
+        let mut storage = BucketMapStorage::with_config(config());
+        for v in &vectors {
+            storage.insert(v.clone()).unwrap();
+        }
+        assert!(storage.num_buckets() > 2);
+        storage.num_buckets()
+    };
+
+    let reopened = BucketMapStorage::with_config(config());
+    assert_eq!(reopened.num_buckets(), num_buckets_before_restart);
+    assert_eq!(reopened.count(), 200);
+    for v in &vectors {
+        let fetched = reopened.get(&v.id).unwrap();
+        assert_eq!(fetched.data, v.data);
+    }
+}
+
+#[test]
+fn test_lsh_index_query_finds_exact_match() {
+    let vectors: Vec<Vector> = generate_random_vectors(32, 50).into_iter().map(Vector::new).collect();
+    let indexed_data: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+
+    let mut index = LshIndex::new(10, 6);
+    index.build(&indexed_data).unwrap();
+
+    let query = vectors[0].clone();
+    let results = index.query(&query.data, 5);
+
+    assert!(!results.is_empty());
+    assert_eq!(results[0].0, query.id);
 } 
\ No newline at end of file
@@ -1,12 +1,47 @@
 use vector_db::{
     vector::Vector,
     local_storage::LocalStorage,
+    embedder::HashedNgramEmbedder,
     utils::generate_random_vectors,
 };
+use vector_db::DedupStats;
 use ndarray::Array1;
 use serde_json::json;
 use tempfile::TempDir;
 use uuid::Uuid;
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::Write;
+
+/// Hand-writes a `vectors.kwi` in the pre-tombstone v1 layout (no tombstone
+/// flag per record) so the migration path can be exercised without a real
+/// v1-era binary around to produce one.
+fn write_legacy_v1_file(path: &std::path::Path, vectors: &[Vector]) {
+    let mut file = std::fs::File::create(path).unwrap();
+    file.write_all(b"KWI\0").unwrap();
+    file.write_u32::<LittleEndian>(1).unwrap(); // KWI_VERSION = 1
+    file.write_u64::<LittleEndian>(vectors.len() as u64).unwrap();
+    file.write_u32::<LittleEndian>(0).unwrap(); // Reserved (unused in v1)
+
+    for vector in vectors {
+        let id_str = vector.id.to_string();
+        file.write_u32::<LittleEndian>(id_str.len() as u32).unwrap();
+        let mut id_bytes = [0u8; 36];
+        id_bytes[..id_str.len()].copy_from_slice(id_str.as_bytes());
+        file.write_all(&id_bytes).unwrap();
+
+        let data_bytes = bincode::serialize(&vector.data).unwrap();
+        file.write_u32::<LittleEndian>(data_bytes.len() as u32).unwrap();
+        file.write_all(&data_bytes).unwrap();
+
+        if let Some(metadata) = &vector.metadata {
+            let metadata_bytes = serde_json::to_string(metadata).unwrap().into_bytes();
+            file.write_u32::<LittleEndian>(metadata_bytes.len() as u32).unwrap();
+            file.write_all(&metadata_bytes).unwrap();
+        } else {
+            file.write_u32::<LittleEndian>(0).unwrap();
+        }
+    }
+}
 
 #[test]
 fn test_local_storage_creation() {
@@ -220,6 +255,145 @@ fn test_local_storage_persistence_across_instances() {
     }
 }
 
+#[test]
+fn test_local_storage_offset_index_persists_across_instances() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let vectors_data = generate_random_vectors(16, 5);
+    let mut vectors = Vec::new();
+
+    {
+        let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+        for data in vectors_data {
+            let vector = Vector::new(data);
+            storage.add_vector(&vector).unwrap();
+            vectors.push(vector);
+        }
+        storage.delete_vector(&vectors[1].id).unwrap();
+
+        assert!(storage.get_storage_path().join("offsets.idx").exists());
+    }
+
+    // Reopening should load the sidecar rather than rescanning, and lookups
+    // should reflect the delete that happened in the previous instance.
+    let storage = LocalStorage::new(temp_dir.path()).unwrap();
+    assert!(storage.get_vector(&vectors[1].id).unwrap().is_none());
+    for (i, vector) in vectors.iter().enumerate() {
+        if i != 1 {
+            assert!(storage.get_vector(&vector.id).unwrap().is_some());
+        }
+    }
+}
+
+#[test]
+fn test_local_storage_rebuilds_offset_index_when_sidecar_missing() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let vector = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0]));
+    {
+        let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+        storage.add_vector(&vector).unwrap();
+    }
+
+    // Simulate a missing/corrupted sidecar: reopening must fall back to a
+    // full rescan and still find the vector.
+    std::fs::remove_file(temp_dir.path().join(".vector_storage").join("offsets.idx")).unwrap();
+
+    let storage = LocalStorage::new(temp_dir.path()).unwrap();
+    let retrieved = storage.get_vector(&vector.id).unwrap();
+    assert!(retrieved.is_some());
+    assert_eq!(retrieved.unwrap().data, vector.data);
+}
+
+#[test]
+fn test_local_storage_upgrades_legacy_v1_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage_dir = temp_dir.path().join(".vector_storage");
+    std::fs::create_dir_all(&storage_dir).unwrap();
+
+    let vector = Vector::with_metadata(
+        Array1::from_vec(vec![1.0, 2.0, 3.0]),
+        json!({"label": "legacy"}),
+    );
+    write_legacy_v1_file(&storage_dir.join("vectors.kwi"), &[vector.clone()]);
+
+    // Opening a v1 file transparently migrates it to the current layout.
+    let storage = LocalStorage::new(temp_dir.path()).unwrap();
+    let retrieved = storage.get_vector(&vector.id).unwrap();
+    assert!(retrieved.is_some());
+    let retrieved = retrieved.unwrap();
+    assert_eq!(retrieved.data, vector.data);
+    assert_eq!(retrieved.metadata, vector.metadata);
+    assert_eq!(storage.get_vector_count().unwrap(), 1);
+}
+
+#[test]
+fn test_local_storage_refuses_newer_version() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage_dir = temp_dir.path().join(".vector_storage");
+    std::fs::create_dir_all(&storage_dir).unwrap();
+
+    let mut file = std::fs::File::create(storage_dir.join("vectors.kwi")).unwrap();
+    file.write_all(b"KWI\0").unwrap();
+    file.write_u32::<LittleEndian>(9999).unwrap(); // Far-future version
+    file.write_u64::<LittleEndian>(0).unwrap();
+    file.write_u32::<LittleEndian>(0).unwrap();
+    drop(file);
+
+    let err = LocalStorage::new(temp_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("9999"));
+}
+
+#[test]
+fn test_local_storage_add_document_and_query_text() {
+    let temp_dir = TempDir::new().unwrap();
+    let embedder = Box::new(HashedNgramEmbedder::new(64));
+    let mut storage = LocalStorage::new_with_embedder(temp_dir.path(), embedder).unwrap();
+
+    storage.add_document("the quick brown fox jumps over the lazy dog", None).unwrap();
+    storage.add_document("exploring the solar system and distant planets", None).unwrap();
+    let cat_id = storage
+        .add_document("the quick brown fox runs past the lazy cat", Some(json!({"source": "test"})))
+        .unwrap();
+
+    let results = storage.query_text("quick brown fox", 2).unwrap();
+    assert_eq!(results.len(), 2);
+
+    // Both fox documents should outrank the unrelated astronomy one, and the
+    // source text should have been folded into the stored metadata.
+    let top_ids: Vec<_> = results.iter().map(|(v, _)| v.id).collect();
+    assert!(top_ids.contains(&cat_id));
+
+    let (cat_doc, _) = results.iter().find(|(v, _)| v.id == cat_id).unwrap();
+    let metadata = cat_doc.metadata.as_ref().unwrap();
+    assert_eq!(metadata["text"], "the quick brown fox runs past the lazy cat");
+    assert_eq!(metadata["source"], "test");
+}
+
+#[test]
+fn test_local_storage_add_document_without_embedder_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+
+    let err = storage.add_document("no embedder configured", None).unwrap_err();
+    assert!(err.to_string().contains("embedder"));
+}
+
+#[test]
+fn test_local_storage_add_document_rejects_dimension_mismatch() {
+    let temp_dir = TempDir::new().unwrap();
+    {
+        let mut storage = LocalStorage::new_with_embedder(temp_dir.path(), Box::new(HashedNgramEmbedder::new(32))).unwrap();
+        storage.add_document("first document", None).unwrap();
+    }
+
+    // Reopening with a differently-sized embedder must be rejected rather
+    // than silently writing vectors of the wrong dimension alongside it.
+    let mut mismatched = LocalStorage::new_with_embedder(temp_dir.path(), Box::new(HashedNgramEmbedder::new(16))).unwrap();
+    let err = mismatched.add_document("second document", None).unwrap_err();
+    assert!(err.to_string().contains("dimensional"));
+}
+
 #[test]
 fn test_local_storage_gitignore_creation() {
     let temp_dir = TempDir::new().unwrap();
@@ -231,4 +405,207 @@ fn test_local_storage_gitignore_creation() {
     // Verify .gitignore content
     let content = std::fs::read_to_string(&gitignore_path).unwrap();
     assert_eq!(content.trim(), "*");
+}
+
+#[test]
+fn test_local_storage_dedups_identical_payloads() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+
+    let data = Array1::from_vec(vec![1.0, 2.0, 3.0]);
+    let first = Vector::new(data.clone());
+    let second = Vector::new(data.clone());
+    storage.add_vector(&first).unwrap();
+    storage.add_vector(&second).unwrap();
+
+    // Both IDs are still directly addressable, and both resolve to the same data.
+    assert_eq!(storage.get_vector_count().unwrap(), 2);
+    assert_eq!(storage.get_vector(&first.id).unwrap().unwrap().data, data);
+    assert_eq!(storage.get_vector(&second.id).unwrap().unwrap().data, data);
+
+    let stats: DedupStats = storage.dedup_stats().unwrap();
+    assert_eq!(stats.distinct_payloads, 1);
+    assert_eq!(stats.duplicate_references, 1);
+    assert!(stats.bytes_saved > 0);
+}
+
+#[test]
+fn test_local_storage_get_all_vectors_resolves_references() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+
+    let data = Array1::from_vec(vec![4.0, 5.0, 6.0]);
+    let first = Vector::new(data.clone());
+    let second = Vector::new(data.clone());
+    storage.add_vector(&first).unwrap();
+    storage.add_vector(&second).unwrap();
+
+    let all = storage.get_all_vectors().unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all.iter().all(|v| v.data == data));
+    assert!(all.iter().any(|v| v.id == first.id));
+    assert!(all.iter().any(|v| v.id == second.id));
+}
+
+#[test]
+fn test_local_storage_deleting_duplicate_keeps_canonical_retrievable() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+
+    let data = Array1::from_vec(vec![7.0, 8.0, 9.0]);
+    let canonical = Vector::new(data.clone());
+    let duplicate = Vector::new(data.clone());
+    storage.add_vector(&canonical).unwrap();
+    storage.add_vector(&duplicate).unwrap();
+
+    storage.delete_vector(&duplicate.id).unwrap();
+
+    assert!(storage.get_vector(&duplicate.id).unwrap().is_none());
+    assert_eq!(storage.get_vector(&canonical.id).unwrap().unwrap().data, data);
+    assert_eq!(storage.get_vector_count().unwrap(), 1);
+
+    let stats = storage.dedup_stats().unwrap();
+    assert_eq!(stats.duplicate_references, 0);
+}
+
+#[test]
+fn test_local_storage_deleting_canonical_keeps_duplicate_retrievable() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+
+    let data = Array1::from_vec(vec![10.0, 11.0, 12.0]);
+    let canonical = Vector::new(data.clone());
+    let duplicate = Vector::new(data.clone());
+    storage.add_vector(&canonical).unwrap();
+    storage.add_vector(&duplicate).unwrap();
+
+    // Deleting the canonical while another record still references its
+    // payload must hide it, not drop the shared data out from under the
+    // duplicate.
+    storage.delete_vector(&canonical.id).unwrap();
+
+    assert!(storage.get_vector(&canonical.id).unwrap().is_none());
+    assert_eq!(storage.get_vector(&duplicate.id).unwrap().unwrap().data, data);
+
+    // Deleting the last referrer should finally drop the shared payload.
+    storage.delete_vector(&duplicate.id).unwrap();
+    assert!(storage.get_vector(&duplicate.id).unwrap().is_none());
+
+    let stats = storage.dedup_stats().unwrap();
+    assert_eq!(stats.distinct_payloads, 0);
+}
+
+#[test]
+fn test_local_storage_compact_preserves_deduplicated_vectors() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut storage = LocalStorage::new_with_compact_threshold(temp_dir.path(), 1.1).unwrap();
+
+    let data = Array1::from_vec(vec![13.0, 14.0, 15.0]);
+    let canonical = Vector::new(data.clone());
+    let duplicate = Vector::new(data.clone());
+    storage.add_vector(&canonical).unwrap();
+    storage.add_vector(&duplicate).unwrap();
+
+    storage.compact().unwrap();
+
+    assert_eq!(storage.get_vector(&canonical.id).unwrap().unwrap().data, data);
+    assert_eq!(storage.get_vector(&duplicate.id).unwrap().unwrap().data, data);
+    assert_eq!(storage.get_vector_count().unwrap(), 2);
+}
+
+#[test]
+fn test_local_storage_dedup_index_persists_across_instances() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let data = Array1::from_vec(vec![16.0, 17.0, 18.0]);
+    let canonical = Vector::new(data.clone());
+    let duplicate = Vector::new(data.clone());
+
+    {
+        let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+        storage.add_vector(&canonical).unwrap();
+        storage.add_vector(&duplicate).unwrap();
+        assert!(storage.get_storage_path().join("dedup.idx").exists());
+    }
+
+    // Reopening should load the dedup sidecar and keep resolving the
+    // duplicate's reference correctly without a full rescan.
+    let storage = LocalStorage::new(temp_dir.path()).unwrap();
+    assert_eq!(storage.get_vector(&duplicate.id).unwrap().unwrap().data, data);
+    assert_eq!(storage.dedup_stats().unwrap().distinct_payloads, 1);
+}
+
+#[test]
+fn test_local_storage_verify_clean_file_reports_no_corruption() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+
+    for data in generate_random_vectors(8, 4) {
+        storage.add_vector(&Vector::new(data)).unwrap();
+    }
+
+    let report = storage.verify().unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.checked, 4);
+}
+
+#[test]
+fn test_local_storage_verify_detects_flipped_data_byte() {
+    let temp_dir = TempDir::new().unwrap();
+    let data = Array1::from_vec(vec![1.0, 2.0, 3.0]);
+    let vector = Vector::new(data);
+    let path;
+
+    {
+        let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+        storage.add_vector(&vector).unwrap();
+        path = storage.get_storage_path().join("vectors.kwi");
+    }
+
+    // Flip a byte inside the record's data payload (past its flag/id_len/id
+    // fields), so it's the trailing CRC32 that catches it rather than a
+    // UTF-8/UUID parsing failure on the id.
+    let header_size = 4 + 4 + 8 + 4; // magic + version + total_count + deleted_count
+    let record_start = header_size;
+    let data_offset = record_start + 1 + 4 + 36 + 4; // flag + id_len + id + data_len
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[data_offset] ^= 0xFF;
+    std::fs::write(&path, &bytes).unwrap();
+
+    let storage = LocalStorage::new(temp_dir.path()).unwrap();
+    let report = storage.verify().unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.corrupt_offsets, vec![record_start as u64]);
+}
+
+#[test]
+fn test_local_storage_repair_drops_corrupt_tail() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = Vector::new(Array1::from_vec(vec![1.0, 2.0]));
+    let second = Vector::new(Array1::from_vec(vec![3.0, 4.0]));
+    let path;
+
+    {
+        let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+        storage.add_vector(&first).unwrap();
+        storage.add_vector(&second).unwrap();
+        path = storage.get_storage_path().join("vectors.kwi");
+    }
+
+    // Corrupt the second record's data payload, leaving the first intact.
+    let header_size = 4 + 4 + 8 + 4;
+    let first_data_len = bincode::serialize(&first.data).unwrap().len();
+    let first_record_size = 1 + 4 + 36 + 4 + first_data_len + 4 + 4; // flag+id_len+id+data_len+data+metadata_len+checksum
+    let second_data_offset = header_size + first_record_size + 1 + 4 + 36 + 4;
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[second_data_offset] ^= 0xFF;
+    std::fs::write(&path, &bytes).unwrap();
+
+    let mut storage = LocalStorage::new(temp_dir.path()).unwrap();
+    let report = storage.repair().unwrap();
+    assert!(!report.is_ok());
+
+    assert_eq!(storage.get_vector(&first.id).unwrap().unwrap().data, first.data);
+    assert!(storage.get_vector(&second.id).unwrap().is_none());
+    assert_eq!(storage.get_vector_count().unwrap(), 1);
 } 
\ No newline at end of file
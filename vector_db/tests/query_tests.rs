@@ -0,0 +1,176 @@
+use vector_db::{index::BruteForceIndex, storage::InMemoryStorage, vector::Vector, AsyncQueryEngine, Index, QueryEngine, ScoreDetail, Storage};
+use ndarray::Array1;
+use serde_json::json;
+use std::sync::Arc;
+
+#[test]
+fn test_search_returns_closest_vector() {
+    let mut storage = InMemoryStorage::new();
+    let a = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let b = Vector::new(Array1::from_vec(vec![0.0, 1.0]));
+    storage.insert(a.clone()).unwrap();
+    storage.insert(b.clone()).unwrap();
+
+    let mut index = BruteForceIndex::new();
+    index
+        .build(&[(&a.id, &a.data), (&b.id, &b.data)])
+        .unwrap();
+
+    let query_engine = QueryEngine::new(&storage, &index);
+    let results = query_engine.search(&a, 1).unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.id, a.id);
+    match &results[0].2 {
+        ScoreDetail::Vector { cosine, euclidean } => {
+            assert!((*cosine - 1.0).abs() < 1e-6);
+            assert!((*euclidean - 0.0).abs() < 1e-6);
+        }
+        other => panic!("expected ScoreDetail::Vector, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_hybrid_search_fuses_vector_and_metadata_rankings() {
+    let mut storage = InMemoryStorage::new();
+
+    // `both` is the closest vector to the query AND matches the metadata
+    // predicate, so it should win the fused ranking no matter how the two
+    // metadata-matching entries happen to be ordered within their list.
+    let both = Vector::with_metadata(
+        Array1::from_vec(vec![1.0, 0.0]),
+        json!({"category": "fruit"}),
+    );
+    // `near` is a close second on vector similarity but has no metadata match.
+    let near = Vector::new(Array1::from_vec(vec![0.9, 0.1]));
+    // `tagged` matches the metadata predicate but is the furthest vector match.
+    let tagged = Vector::with_metadata(
+        Array1::from_vec(vec![0.0, 1.0]),
+        json!({"category": "fruit"}),
+    );
+
+    for v in [&both, &near, &tagged] {
+        storage.insert(v.clone()).unwrap();
+    }
+
+    let mut index = BruteForceIndex::new();
+    index
+        .build(&[(&both.id, &both.data), (&near.id, &near.data), (&tagged.id, &tagged.data)])
+        .unwrap();
+
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let query_engine = QueryEngine::new(&storage, &index);
+
+    let predicate = |metadata: &serde_json::Value| metadata["category"] == "fruit";
+    let results = query_engine.hybrid_search(&query, predicate, 3).unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].0.id, both.id);
+    match &results[0].2 {
+        ScoreDetail::Fused { vector_rank, metadata_rank, .. } => {
+            assert_eq!(*vector_rank, Some(1));
+            assert!(metadata_rank.is_some());
+        }
+        other => panic!("expected ScoreDetail::Fused, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_hybrid_search_respects_top_k() {
+    let mut storage = InMemoryStorage::new();
+    let vectors: Vec<Vector> = (0..5)
+        .map(|i| Vector::with_metadata(Array1::from_vec(vec![i as f32, 0.0]), json!({"tag": "x"})))
+        .collect();
+    for v in &vectors {
+        storage.insert(v.clone()).unwrap();
+    }
+
+    let mut index = BruteForceIndex::new();
+    let indexed: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+    index.build(&indexed).unwrap();
+
+    let query = Vector::new(Array1::from_vec(vec![0.0, 0.0]));
+    let query_engine = QueryEngine::new(&storage, &index);
+
+    let results = query_engine
+        .hybrid_search(&query, |metadata| metadata["tag"] == "x", 2)
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+}
+
+#[test]
+fn test_search_with_threshold_drops_weak_matches() {
+    let mut storage = InMemoryStorage::new();
+    let close = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let far = Vector::new(Array1::from_vec(vec![0.0, 1.0]));
+    storage.insert(close.clone()).unwrap();
+    storage.insert(far.clone()).unwrap();
+
+    let mut index = BruteForceIndex::new();
+    index
+        .build(&[(&close.id, &close.data), (&far.id, &far.data)])
+        .unwrap();
+
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let query_engine = QueryEngine::new(&storage, &index);
+
+    let results = query_engine.search_with_threshold(&query, 2, 0.5).unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.id, close.id);
+}
+
+#[tokio::test]
+async fn test_async_query_engine_find_similar_respects_threshold_and_limit() {
+    let mut storage = InMemoryStorage::new();
+    let close = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let far = Vector::new(Array1::from_vec(vec![0.0, 1.0]));
+    storage.insert(close.clone()).unwrap();
+    storage.insert(far.clone()).unwrap();
+
+    let mut index = BruteForceIndex::new();
+    index
+        .build(&[(&close.id, &close.data), (&far.id, &far.data)])
+        .unwrap();
+
+    let engine = AsyncQueryEngine::new(Arc::new(storage), Arc::new(index));
+
+    let query = Vector::new(Array1::from_vec(vec![1.0, 0.0]));
+    let results = engine.find_similar(&query, 0.5, 2).await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.id, close.id);
+}
+
+#[tokio::test]
+async fn test_async_query_engine_handles_concurrent_queries() {
+    let mut storage = InMemoryStorage::new();
+    let vectors: Vec<Vector> = (0..5)
+        .map(|i| Vector::new(Array1::from_vec(vec![i as f32, 0.0])))
+        .collect();
+    for v in &vectors {
+        storage.insert(v.clone()).unwrap();
+    }
+
+    let mut index = BruteForceIndex::new();
+    let indexed: Vec<_> = vectors.iter().map(|v| (&v.id, &v.data)).collect();
+    index.build(&indexed).unwrap();
+
+    let engine = Arc::new(AsyncQueryEngine::new(Arc::new(storage), Arc::new(index)));
+    let query = Vector::new(Array1::from_vec(vec![0.0, 0.0]));
+
+    let mut handles = Vec::new();
+    for _ in 0..10 {
+        let engine = Arc::clone(&engine);
+        let query = query.clone();
+        handles.push(tokio::spawn(async move {
+            engine.find_similar(&query, -1.0, 5).await.unwrap()
+        }));
+    }
+
+    for handle in handles {
+        let results = handle.await.unwrap();
+        assert_eq!(results.len(), 5);
+    }
+}
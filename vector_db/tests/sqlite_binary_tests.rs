@@ -1,11 +1,14 @@
 use vector_db::{
     vector::Vector,
     collection_manager::CollectionManager,
-    sqlite_storage::SQLiteStorage,
-    binary_index::BinaryIndex,
+    sqlite_storage::{PooledSQLiteStorage, SQLiteStorage},
+    binary_index::{BinaryIndex, BinaryIndexConfig, BinaryIndexOpener, CompressionAlgorithm, EncryptionType},
+    embedder::HashedNgramEmbedder,
     utils::generate_random_vectors,
+    BackendKind, ImportOutcome, VectorDBError,
 };
 use ndarray::Array1;
+use std::time::Duration;
 use tempfile::TempDir;
 use uuid::Uuid;
 
@@ -54,6 +57,169 @@ fn test_sqlite_storage_vector_operations() {
     assert!(storage.get_vector(&vector.id).unwrap().is_none());
 }
 
+#[test]
+fn test_sqlite_storage_insert_vectors_bulk() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.sqlite3");
+
+    let storage = SQLiteStorage::new(&db_path, "test_collection").unwrap();
+
+    let vectors: Vec<Vector> = generate_random_vectors(8, 100)
+        .into_iter()
+        .map(Vector::new)
+        .collect();
+
+    storage.insert_vectors(&vectors).unwrap();
+    assert_eq!(storage.count_vectors().unwrap(), 100);
+
+    for v in &vectors {
+        let retrieved = storage.get_vector(&v.id).unwrap();
+        assert_eq!(retrieved.unwrap().data, v.data);
+    }
+}
+
+#[test]
+fn test_sqlite_storage_delete_vector_tombstones_instead_of_removing() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.sqlite3");
+
+    let storage = SQLiteStorage::new(&db_path, "test_collection").unwrap();
+
+    let data = Array1::from_vec(vec![1.0, 2.0, 3.0]);
+    let vector = Vector::new(data);
+    storage.insert_vector(&vector).unwrap();
+
+    let (_, version_before) = storage.get_vector_with_version(&vector.id).unwrap().unwrap();
+    assert_eq!(version_before, 1);
+
+    storage.delete_vector(&vector.id).unwrap();
+
+    // A normal read no longer sees the row...
+    assert!(storage.get_vector(&vector.id).unwrap().is_none());
+    assert_eq!(storage.count_vectors().unwrap(), 0);
+    assert!(!storage.get_all_vectors().unwrap().iter().any(|v| v.id == vector.id));
+
+    // ...but it's a tombstone, not gone, and its version advanced.
+    let (retrieved, version_after) = storage.get_vector_with_version(&vector.id).unwrap().unwrap();
+    assert_eq!(retrieved.id, vector.id);
+    assert_eq!(version_after, 2);
+}
+
+#[test]
+fn test_sqlite_storage_purge_tombstones_reclaims_old_deletes() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.sqlite3");
+
+    let storage = SQLiteStorage::new(&db_path, "test_collection").unwrap();
+
+    let vectors: Vec<Vector> = generate_random_vectors(8, 2)
+        .into_iter()
+        .map(Vector::new)
+        .collect();
+    storage.insert_vectors(&vectors).unwrap();
+    storage.delete_vector(&vectors[0].id).unwrap();
+
+    // A cutoff before the tombstone was written purges nothing.
+    let purged = storage
+        .purge_tombstones(std::time::SystemTime::now() - std::time::Duration::from_secs(3600))
+        .unwrap();
+    assert_eq!(purged, 0);
+    assert!(storage.get_vector_with_version(&vectors[0].id).unwrap().is_some());
+
+    // A cutoff after the tombstone was written reclaims it for good.
+    let purged = storage
+        .purge_tombstones(std::time::SystemTime::now() + std::time::Duration::from_secs(1))
+        .unwrap();
+    assert_eq!(purged, 1);
+    assert!(storage.get_vector_with_version(&vectors[0].id).unwrap().is_none());
+
+    // The surviving vector is untouched.
+    assert!(storage.get_vector(&vectors[1].id).unwrap().is_some());
+}
+
+#[test]
+fn test_sqlite_storage_open_read_only_rejects_mutation() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.sqlite3");
+
+    let vector = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0]));
+    {
+        let storage = SQLiteStorage::new(&db_path, "test_collection").unwrap();
+        storage.insert_vector(&vector).unwrap();
+        storage.close().unwrap();
+    }
+
+    let reader = SQLiteStorage::open_read_only(&db_path, "test_collection").unwrap();
+    let retrieved = reader.get_vector(&vector.id).unwrap().unwrap();
+    assert_eq!(retrieved.data, vector.data);
+
+    let err = reader.insert_vector(&vector).unwrap_err();
+    assert!(matches!(err, VectorDBError::StorageError(_)));
+
+    let err = reader.delete_vector(&vector.id).unwrap_err();
+    assert!(matches!(err, VectorDBError::StorageError(_)));
+}
+
+#[test]
+fn test_sqlite_storage_commit_and_close_persist_writes() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.sqlite3");
+
+    let vector = Vector::new(Array1::from_vec(vec![4.0, 5.0, 6.0]));
+    {
+        let storage = SQLiteStorage::new(&db_path, "test_collection").unwrap();
+        storage.insert_vector(&vector).unwrap();
+        storage.commit().unwrap();
+        storage.close().unwrap();
+    }
+
+    // Reopening sees the committed write even though the handle above was
+    // closed explicitly rather than dropped.
+    let storage = SQLiteStorage::new(&db_path, "test_collection").unwrap();
+    assert!(storage.get_vector(&vector.id).unwrap().is_some());
+}
+
+#[test]
+fn test_pooled_sqlite_storage_readers_and_writer_split() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.sqlite3");
+
+    let pool = PooledSQLiteStorage::new(
+        &db_path,
+        "test_collection",
+        4,
+        Duration::from_secs(3600),
+        Duration::from_secs(3600),
+    ).unwrap();
+
+    let vectors: Vec<Vector> = generate_random_vectors(8, 20)
+        .into_iter()
+        .map(Vector::new)
+        .collect();
+    for vector in &vectors {
+        pool.insert_vector(vector).unwrap();
+    }
+    assert_eq!(pool.count_vectors().unwrap(), 20);
+
+    // Fan reads out across threads; each should see the fully written pool.
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            let pool = &pool;
+            let vectors = &vectors;
+            scope.spawn(move || {
+                for vector in vectors {
+                    let retrieved = pool.get_vector(&vector.id).unwrap().unwrap();
+                    assert_eq!(retrieved.data, vector.data);
+                }
+            });
+        }
+    });
+
+    pool.delete_vector(&vectors[0].id).unwrap();
+    assert_eq!(pool.count_vectors().unwrap(), 19);
+    assert!(pool.get_vector(&vectors[0].id).unwrap().is_none());
+}
+
 #[test]
 fn test_binary_index_basic_operations() {
     let temp_dir = TempDir::new().unwrap();
@@ -167,6 +333,255 @@ fn test_binary_index_optimization() {
     assert_eq!(all_vectors.len(), initial_count);
 }
 
+#[test]
+fn test_binary_index_tombstone_and_compact() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("test.kwi");
+
+    let mut index = BinaryIndex::new(&index_path, 8).unwrap();
+
+    let vectors_data = generate_random_vectors(8, 4);
+    let vectors: Vec<_> = vectors_data.into_iter().map(Vector::new).collect();
+    for vector in &vectors {
+        index.add_vector(vector).unwrap();
+    }
+
+    // Deleting tombstones rather than removing the record outright.
+    index.delete_vector(&vectors[0].id).unwrap();
+    assert_eq!(index.count_vectors(), 3);
+    assert!(index.get_vector(&vectors[0].id).unwrap().is_none());
+    assert!(index.dead_bytes() > 0);
+
+    let stats = index.compact().unwrap();
+    assert_eq!(stats.live_vectors, 3);
+    assert_eq!(stats.dead_vectors, 1);
+    assert!(stats.reclaimed_bytes > 0);
+
+    // Space was reclaimed and remaining vectors are still retrievable.
+    assert_eq!(index.dead_bytes(), 0);
+    assert_eq!(index.count_vectors(), 3);
+    for vector in &vectors[1..] {
+        assert!(index.get_vector(&vector.id).unwrap().is_some());
+    }
+}
+
+#[test]
+fn test_binary_index_auto_compact_threshold() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("test.kwi");
+
+    let config = BinaryIndexConfig {
+        auto_compact_threshold: Some(0.1),
+        ..BinaryIndexConfig::default()
+    };
+    let mut index = BinaryIndex::new_with_config(&index_path, 8, config).unwrap();
+
+    let vectors_data = generate_random_vectors(8, 6);
+    let vectors: Vec<_> = vectors_data.into_iter().map(Vector::new).collect();
+    for vector in &vectors {
+        index.add_vector(vector).unwrap();
+    }
+
+    for vector in &vectors[..5] {
+        index.delete_vector(&vector.id).unwrap();
+    }
+
+    // Crossing the low threshold should have triggered a compaction already.
+    assert_eq!(index.dead_bytes(), 0);
+    assert_eq!(index.count_vectors(), 1);
+}
+
+#[test]
+fn test_binary_index_compression_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("test.kwi");
+
+    let config = BinaryIndexConfig {
+        compression: CompressionAlgorithm::Lz4,
+        ..BinaryIndexConfig::default()
+    };
+    let mut index = BinaryIndex::new_with_config(&index_path, 32, config).unwrap();
+
+    // A repetitive vector compresses well.
+    let data = Array1::from_vec(vec![1.0f32; 32]);
+    let metadata = serde_json::json!({"label": "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"});
+    let vector = Vector::with_metadata(data, metadata);
+    index.add_vector(&vector).unwrap();
+
+    let retrieved = index.get_vector(&vector.id).unwrap().unwrap();
+    assert_eq!(retrieved.data, vector.data);
+    assert_eq!(retrieved.metadata, vector.metadata);
+
+    let (logical, physical) = index.compression_stats();
+    assert!(physical < logical, "compressed form should be smaller");
+}
+
+#[test]
+fn test_binary_index_encryption_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("test.kwi");
+
+    let opener = BinaryIndexOpener::with_passphrase(EncryptionType::AesGcm, "correct horse battery staple");
+    let mut index = BinaryIndex::new_with_opener(&index_path, 8, BinaryIndexConfig::default(), opener).unwrap();
+
+    let data = Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+    let metadata = serde_json::json!({"label": "secret"});
+    let vector = Vector::with_metadata(data, metadata);
+    index.add_vector(&vector).unwrap();
+    drop(index);
+
+    // Re-opening with the right passphrase decrypts the vector back out.
+    let opener = BinaryIndexOpener::with_passphrase(EncryptionType::AesGcm, "correct horse battery staple");
+    let index = BinaryIndex::new_with_opener(&index_path, 8, BinaryIndexConfig::default(), opener).unwrap();
+    let retrieved = index.get_vector(&vector.id).unwrap().unwrap();
+    assert_eq!(retrieved.data, vector.data);
+    assert_eq!(retrieved.metadata, vector.metadata);
+
+    // The raw file contents must not contain the plaintext label.
+    let raw = std::fs::read(&index_path).unwrap();
+    let raw_str = String::from_utf8_lossy(&raw);
+    assert!(!raw_str.contains("secret"));
+
+    // Opening without a passphrase is rejected.
+    let err = BinaryIndex::new_with_opener(&index_path, 8, BinaryIndexConfig::default(), BinaryIndexOpener::none())
+        .unwrap_err();
+    assert!(matches!(err, VectorDBError::IntegrityError(_)));
+
+    // Opening with the wrong passphrase fails to authenticate.
+    let wrong_opener = BinaryIndexOpener::with_passphrase(EncryptionType::AesGcm, "wrong passphrase");
+    let wrong_index =
+        BinaryIndex::new_with_opener(&index_path, 8, BinaryIndexConfig::default(), wrong_opener).unwrap();
+    let err = wrong_index.get_vector(&vector.id).unwrap_err();
+    assert!(matches!(err, VectorDBError::IntegrityError(_)));
+}
+
+#[test]
+fn test_binary_index_verify_detects_corruption() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("test.kwi");
+
+    let mut index = BinaryIndex::new(&index_path, 4).unwrap();
+    let data = Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0]);
+    let vector = Vector::new(data);
+    index.add_vector(&vector).unwrap();
+
+    let report = index.verify().unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.checked, 1);
+
+    // Flip a byte in the middle of the segment file to corrupt the stored record.
+    drop(index);
+    let segment_path = temp_dir.path().join("test.000.kwi");
+    let mut bytes = std::fs::read(&segment_path).unwrap();
+    let mid = bytes.len() / 2;
+    bytes[mid] ^= 0xFF;
+    std::fs::write(&segment_path, bytes).unwrap();
+
+    let index = BinaryIndex::new(&index_path, 4).unwrap();
+    let report = index.verify().unwrap();
+    assert_eq!(report.checked, 1);
+    assert_eq!(report.corrupt.len(), 1);
+    assert_eq!(report.corrupt[0].0, vector.id);
+
+    let err = index.get_vector(&vector.id).unwrap_err();
+    assert!(matches!(err, VectorDBError::IntegrityError(_)));
+}
+
+#[test]
+fn test_binary_index_recovers_from_log_without_flush() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("test.kwi");
+
+    let vectors_data = generate_random_vectors(8, 4);
+    let vectors: Vec<_> = vectors_data.into_iter().map(Vector::new).collect();
+
+    {
+        let mut index = BinaryIndex::new(&index_path, 8).unwrap();
+        for vector in &vectors {
+            index.add_vector(vector).unwrap();
+        }
+        index.delete_vector(&vectors[0].id).unwrap();
+        // No explicit flush(): everything since the header was written lives
+        // only in the append-only index-log until Drop checkpoints it.
+        std::mem::forget(index);
+    }
+
+    // Reopening replays the log on top of the (still-empty) footer, so every
+    // write survives even though the main file was never rewritten.
+    let index = BinaryIndex::new(&index_path, 8).unwrap();
+    assert_eq!(index.count_vectors(), 3);
+    assert!(index.get_vector(&vectors[0].id).unwrap().is_none());
+    for vector in &vectors[1..] {
+        assert_eq!(index.get_vector(&vector.id).unwrap().unwrap().data, vector.data);
+    }
+}
+
+#[test]
+fn test_binary_index_dump_restore_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("test.kwi");
+
+    let mut index = BinaryIndex::new(&index_path, 4).unwrap();
+    let vectors: Vec<Vector> = (0..3)
+        .map(|i| Vector::with_metadata(Array1::from_vec(vec![i as f32; 4]), serde_json::json!({"i": i})))
+        .collect();
+    for vector in &vectors {
+        index.add_vector(vector).unwrap();
+    }
+
+    let mut dump = Vec::new();
+    let written = index.dump(&mut dump).unwrap();
+    assert_eq!(written, 3);
+
+    let restore_path = temp_dir.path().join("restored.kwi");
+    let restored = BinaryIndex::restore(dump.as_slice(), &restore_path, 4).unwrap();
+    assert_eq!(restored.count_vectors(), 3);
+
+    for vector in &vectors {
+        let retrieved = restored.get_vector(&vector.id).unwrap().unwrap();
+        assert_eq!(retrieved.data, vector.data);
+        assert_eq!(retrieved.metadata, vector.metadata);
+    }
+}
+
+#[test]
+fn test_binary_index_segment_rollover() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_path = temp_dir.path().join("vectors.kwi");
+
+    // Cap segments tiny so a handful of vectors force a rollover.
+    let config = BinaryIndexConfig {
+        max_segment_size: 64,
+        ..BinaryIndexConfig::default()
+    };
+    let mut index = BinaryIndex::new_with_config(&index_path, 8, config).unwrap();
+
+    let vectors_data = generate_random_vectors(8, 5);
+    let vectors: Vec<_> = vectors_data.into_iter().map(Vector::new).collect();
+    for vector in &vectors {
+        index.add_vector(vector).unwrap();
+    }
+
+    assert!(
+        temp_dir.path().join("vectors.001.kwi").exists(),
+        "expected a second segment file after exceeding the size cap"
+    );
+
+    for vector in &vectors {
+        let retrieved = index.get_vector(&vector.id).unwrap().unwrap();
+        assert_eq!(retrieved.data, vector.data);
+    }
+
+    // Compaction should carry segments over under the final naming and
+    // drop the old ones.
+    index.delete_vector(&vectors[0].id).unwrap();
+    index.compact().unwrap();
+    assert_eq!(index.count_vectors(), 4);
+    for vector in &vectors[1..] {
+        assert!(index.get_vector(&vector.id).unwrap().is_some());
+    }
+}
+
 #[test]
 fn test_collection_manager_basic_operations() {
     let temp_dir = TempDir::new().unwrap();
@@ -310,4 +725,310 @@ fn test_collection_manager_optimization() {
     
     let all_vectors = manager.get_all_vectors("test_collection").unwrap();
     assert_eq!(all_vectors.len(), 10);
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_collection_manager_hybrid_search_fuses_vector_and_keyword_scores() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 4).unwrap();
+
+    let target = Array1::from_vec(vec![1.0, 0.0, 0.0, 0.0]);
+    let near_vector_far_text = Vector::with_metadata(
+        Array1::from_vec(vec![0.99, 0.01, 0.0, 0.0]),
+        serde_json::json!({"text": "completely unrelated content"}),
+    );
+    let far_vector_near_text = Vector::with_metadata(
+        Array1::from_vec(vec![0.0, 0.0, 1.0, 0.0]),
+        serde_json::json!({"text": "rust vector database"}),
+    );
+    manager.add_vector("docs", &near_vector_far_text).unwrap();
+    manager.add_vector("docs", &far_vector_near_text).unwrap();
+
+    // Pure vector search favors the vector-similar document.
+    let vector_only = manager.hybrid_search("docs", &target, "rust vector database", 2, 1.0).unwrap();
+    assert_eq!(vector_only[0].0.id, near_vector_far_text.id);
+
+    // Pure keyword search favors the text-similar document.
+    let text_only = manager.hybrid_search("docs", &target, "rust vector database", 2, 0.0).unwrap();
+    assert_eq!(text_only[0].0.id, far_vector_near_text.id);
+}
+
+#[test]
+fn test_collection_manager_create_with_embedder_rejects_dimension_mismatch() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+
+    let embedder = Box::new(HashedNgramEmbedder::new(32));
+    let result = manager.create_collection_with_embedder("docs", 64, embedder);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_collection_manager_add_and_search_text() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+
+    let embedder = Box::new(HashedNgramEmbedder::new(64));
+    manager.create_collection_with_embedder("docs", 64, embedder).unwrap();
+
+    manager.add_text("docs", "the quick brown fox", None).unwrap();
+    manager.add_text("docs", "lazy dogs sleep all day", None).unwrap();
+
+    assert_eq!(manager.count_vectors("docs").unwrap(), 2);
+
+    let results = manager.search_text("docs", "the quick brown fox", 1).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.metadata.as_ref().unwrap()["text"], "the quick brown fox");
+}
+
+#[test]
+fn test_collection_manager_add_text_without_embedder_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 64).unwrap();
+
+    let result = manager.add_text("docs", "no embedder configured here", None);
+    assert!(result.is_err());
+} 
+#[test]
+fn test_collection_manager_snapshot_and_restore_collection_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let snapshot_root = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 4).unwrap();
+
+    let vectors: Vec<Vector> = generate_random_vectors(4, 5).into_iter().map(Vector::new).collect();
+    for v in &vectors {
+        manager.add_vector("docs", v).unwrap();
+    }
+
+    let snapshot_dir = manager.snapshot_collection("docs", snapshot_root.path()).unwrap();
+    assert!(snapshot_dir.join("metadata.sqlite3").exists());
+    assert!(snapshot_dir.join("vectors.kwi").exists());
+
+    let extra = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0]));
+    manager.add_vector("docs", &extra).unwrap();
+    assert_eq!(manager.count_vectors("docs").unwrap(), 6);
+
+    manager.restore_collection("docs", &snapshot_dir).unwrap();
+    assert_eq!(manager.count_vectors("docs").unwrap(), 5);
+    assert!(manager.get_vector("docs", &extra.id).unwrap().is_none());
+    for v in &vectors {
+        assert!(manager.get_vector("docs", &v.id).unwrap().is_some());
+    }
+}
+
+#[test]
+fn test_collection_manager_vacuum_collection_reclaims_tombstoned_space() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 4).unwrap();
+
+    let vectors: Vec<Vector> = generate_random_vectors(4, 10).into_iter().map(Vector::new).collect();
+    for v in &vectors {
+        manager.add_vector("docs", v).unwrap();
+    }
+    for v in &vectors[..8] {
+        manager.delete_vector("docs", &v.id).unwrap();
+    }
+
+    manager.vacuum_collection("docs").unwrap();
+
+    let info = manager.get_collection_info("docs").unwrap();
+    assert!(info.contains_key("vector_count"));
+    assert_eq!(manager.count_vectors("docs").unwrap(), 2);
+}
+
+#[test]
+fn test_collection_manager_in_memory_backend_round_trips_without_touching_disk() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection_with_backend("scratch", 4, BackendKind::InMemory).unwrap();
+
+    let info = manager.get_collection_info("scratch").unwrap();
+    assert_eq!(info.get("backend").unwrap(), "in_memory");
+
+    let vector = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0]));
+    manager.add_vector("scratch", &vector).unwrap();
+    assert_eq!(manager.count_vectors("scratch").unwrap(), 1);
+    assert!(manager.get_vector("scratch", &vector.id).unwrap().is_some());
+
+    assert!(!temp_dir.path().join("scratch").join("metadata.sqlite3").exists());
+}
+
+#[test]
+fn test_collection_manager_json_backend_persists_and_reloads() {
+    let temp_dir = TempDir::new().unwrap();
+    let vectors: Vec<Vector> = generate_random_vectors(4, 5).into_iter().map(Vector::new).collect();
+
+    {
+        let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+        manager.create_collection_with_backend("docs", 4, BackendKind::Json).unwrap();
+        for v in &vectors {
+            manager.add_vector("docs", v).unwrap();
+        }
+
+        let info = manager.get_collection_info("docs").unwrap();
+        assert_eq!(info.get("backend").unwrap(), "json");
+    }
+
+    assert!(temp_dir.path().join("docs").join("vectors.json").exists());
+
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    assert_eq!(manager.count_vectors("docs").unwrap(), 5);
+    for v in &vectors {
+        let fetched = manager.get_vector("docs", &v.id).unwrap().unwrap();
+        assert_eq!(fetched.data, v.data);
+    }
+}
+
+#[test]
+fn test_collection_manager_add_vector_dedups_identical_payloads() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 4).unwrap();
+
+    let data = Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0]);
+    let original = Vector::new(data.clone());
+    let duplicate = Vector::new(data);
+
+    let first_id = manager.add_vector("docs", &original).unwrap();
+    assert_eq!(first_id, original.id);
+
+    let second_id = manager.add_vector("docs", &duplicate).unwrap();
+    assert_eq!(second_id, original.id);
+    assert_ne!(second_id, duplicate.id);
+
+    assert_eq!(manager.count_vectors("docs").unwrap(), 1);
+}
+
+#[test]
+fn test_collection_manager_import_dir_reports_added_duplicate_and_failed() {
+    let temp_dir = TempDir::new().unwrap();
+    let import_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 4).unwrap();
+
+    let vector = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0]));
+    std::fs::write(
+        import_dir.path().join("a.json"),
+        serde_json::to_string(&vector).unwrap(),
+    ).unwrap();
+    std::fs::write(
+        import_dir.path().join("b.json"),
+        serde_json::to_string(&Vector::new(vector.data.clone())).unwrap(),
+    ).unwrap();
+    std::fs::write(import_dir.path().join("c.json"), "not valid json").unwrap();
+
+    let results = manager.import_dir("docs", import_dir.path()).unwrap();
+    assert_eq!(results.len(), 3);
+
+    let outcomes: Vec<&str> = results.iter().map(|entry| match entry.outcome {
+        ImportOutcome::Added(_) => "added",
+        ImportOutcome::SkippedDuplicate(_) => "duplicate",
+        ImportOutcome::Failed(_) => "failed",
+    }).collect();
+    assert_eq!(outcomes, vec!["added", "duplicate", "failed"]);
+    assert_eq!(manager.count_vectors("docs").unwrap(), 1);
+}
+
+#[test]
+fn test_transaction_commit_applies_inserts_and_deletes_atomically() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 4).unwrap();
+
+    let vectors: Vec<Vector> = generate_random_vectors(4, 3).into_iter().map(Vector::new).collect();
+    for v in &vectors {
+        manager.add_vector("docs", v).unwrap();
+    }
+
+    let mut txn = manager.begin("docs").unwrap();
+    let new_vector = Vector::new(Array1::from_vec(vec![9.0, 9.0, 9.0, 9.0]));
+    txn.insert(new_vector.clone());
+    txn.delete(vectors[0].id);
+
+    manager.commit(txn).unwrap();
+
+    assert_eq!(manager.count_vectors("docs").unwrap(), 3);
+    assert!(manager.get_vector("docs", &vectors[0].id).unwrap().is_none());
+    assert!(manager.get_vector("docs", &new_vector.id).unwrap().is_some());
+}
+
+#[test]
+fn test_transaction_rollback_discards_staged_writes() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 4).unwrap();
+
+    let vector = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0]));
+    manager.add_vector("docs", &vector).unwrap();
+
+    let mut txn = manager.begin("docs").unwrap();
+    txn.delete(vector.id);
+    txn.insert(Vector::new(Array1::from_vec(vec![5.0, 6.0, 7.0, 8.0])));
+
+    manager.rollback(txn);
+
+    assert_eq!(manager.count_vectors("docs").unwrap(), 1);
+    assert!(manager.get_vector("docs", &vector.id).unwrap().is_some());
+}
+
+#[test]
+fn test_transaction_reads_its_own_staged_writes_before_commit() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    manager.create_collection("docs", 4).unwrap();
+
+    let existing = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0]));
+    manager.add_vector("docs", &existing).unwrap();
+
+    let mut txn = manager.begin("docs").unwrap();
+    let staged = Vector::new(Array1::from_vec(vec![5.0, 6.0, 7.0, 8.0]));
+    txn.insert(staged.clone());
+    txn.delete(existing.id);
+
+    // The transaction's own view already reflects its staged writes...
+    assert!(txn.get(&staged.id).is_some());
+    assert!(txn.get(&existing.id).is_none());
+    assert_eq!(txn.all_vectors().len(), 1);
+
+    // ...but nothing else has committed yet, so the manager's own reads
+    // still see the pre-transaction state.
+    assert_eq!(manager.count_vectors("docs").unwrap(), 1);
+    assert!(manager.get_vector("docs", &existing.id).unwrap().is_some());
+    assert!(manager.get_vector("docs", &staged.id).unwrap().is_none());
+
+    manager.commit(txn).unwrap();
+    assert_eq!(manager.count_vectors("docs").unwrap(), 1);
+    assert!(manager.get_vector("docs", &staged.id).unwrap().is_some());
+}
+
+#[test]
+fn test_collection_manager_replays_write_ahead_marker_left_by_a_crash() {
+    let temp_dir = TempDir::new().unwrap();
+    let vector = Vector::new(Array1::from_vec(vec![1.0, 2.0, 3.0, 4.0]));
+
+    {
+        let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+        manager.create_collection("docs", 4).unwrap();
+
+        // Simulate a crash between writing the write-ahead marker and
+        // applying it to the backend: write the marker directly, without
+        // ever calling `commit`.
+        let marker = serde_json::json!({
+            "inserts": [vector],
+            "deletes": [],
+        });
+        std::fs::write(
+            temp_dir.path().join("docs").join(".wal-1"),
+            serde_json::to_string(&marker).unwrap(),
+        ).unwrap();
+    }
+
+    let mut manager = CollectionManager::new(temp_dir.path()).unwrap();
+    assert_eq!(manager.count_vectors("docs").unwrap(), 1);
+    assert!(manager.get_vector("docs", &vector.id).unwrap().is_some());
+    assert!(!temp_dir.path().join("docs").join(".wal-1").exists());
+}